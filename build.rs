@@ -0,0 +1,28 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// jemalloc-sys builds jemalloc with the `_rjem_` prefix unless unprefixed symbols are requested and
+// supported by the target. The prefix also applies to the environment variable and symbolic link
+// jemalloc reads its options from, so it's detected from the header jemalloc-sys installs and
+// exposed as the `prefixed` cfg, as jemalloc-sys does internally.
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(prefixed)");
+
+    let prefixed = match env::var_os("DEP_JEMALLOC_ROOT") {
+        Some(root) => {
+            let header = Path::new(&root).join("include/jemalloc/jemalloc.h");
+            println!("cargo:rerun-if-changed={}", header.display());
+            fs::read_to_string(&header)
+                .map(|header| header.contains("_rjem_malloc_conf"))
+                .unwrap_or(true)
+        }
+        // jemalloc-sys only skips its build when linking a jemalloc provided with
+        // `JEMALLOC_OVERRIDE`, in which case it uses unprefixed symbols
+        None => false,
+    };
+
+    if prefixed {
+        println!("cargo:rustc-cfg=prefixed");
+    }
+}
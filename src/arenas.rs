@@ -1,10 +1,27 @@
 //! Arena operations.
-use std::io;
-use std::os::raw::{c_char, c_uint};
+use jemalloc_sys::{self, extent_hooks_t};
+use libc::ssize_t;
+use std::os::raw::{c_int, c_uint};
 
-use {get, get_mib, name_to_mib};
+use extent_hooks::{self, ExtentHooks};
+use {get, get_set, get_set_mib, name_string, optional, Error, Mib, Result};
 
-const NARENAS: *const c_char = b"arenas.narenas\0" as *const _ as *const _;
+/// A pseudo-index referring to all arenas.
+///
+/// When used with statistics, this accesses values merged across all arenas.
+///
+/// This corresponds to `MALLCTL_ARENAS_ALL` in jemalloc's API.
+pub const ALL: c_uint = 4096;
+
+/// A pseudo-index referring to all destroyed arenas.
+///
+/// When used with statistics, this accesses values merged across all arenas which have been
+/// destroyed.
+///
+/// This corresponds to `MALLCTL_ARENAS_DESTROYED` in jemalloc's API.
+pub const DESTROYED: c_uint = 4097;
+
+const NARENAS: &[u8] = b"arenas.narenas\0";
 
 /// Returns the current limit on the number of arenas.
 ///
@@ -21,7 +38,7 @@ const NARENAS: *const c_char = b"arenas.narenas\0" as *const _ as *const _;
 ///     println!("number of arenas: {}", jemalloc_ctl::arenas::narenas().unwrap());
 /// }
 /// ```
-pub fn narenas() -> io::Result<c_uint> {
+pub fn narenas() -> Result<c_uint> {
     unsafe { get(NARENAS) }
 }
 
@@ -45,20 +62,1753 @@ pub fn narenas() -> io::Result<c_uint> {
 /// }
 /// ```
 #[derive(Copy, Clone)]
-pub struct NArenas([usize; 2]);
+pub struct NArenas(Mib<c_uint, [usize; 2]>);
 
 impl NArenas {
     /// Returns a new `NArenas`.
-    pub fn new() -> io::Result<NArenas> {
-        unsafe {
-            let mut mib = [0; 2];
-            name_to_mib(NARENAS, &mut mib)?;
-            Ok(NArenas(mib))
-        }
+    pub fn new() -> Result<NArenas> {
+        unsafe { Mib::new(NARENAS).map(NArenas) }
     }
 
     /// Returns the maximum number of arenas.
-    pub fn get(&self) -> io::Result<c_uint> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<c_uint> {
+        self.0.read()
+    }
+}
+
+/// The index of an arena.
+///
+/// Arenas created with [`create`] are not used by any thread until they are explicitly selected,
+/// either through the `MALLOCX_ARENA` flag (see [`ArenaId::flags`]) or by binding a thread to the
+/// arena.
+///
+/// [`create`]: fn.create.html
+/// [`ArenaId::flags`]: #method.flags
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArenaId(c_uint);
+
+impl ArenaId {
+    /// Returns the arena's index.
+    pub fn index(&self) -> c_uint {
+        self.0
+    }
+
+    /// Returns the flags selecting this arena, for use with `mallocx` and related functions.
+    ///
+    /// This is `MALLOCX_ARENA` combined with `MALLOCX_TCACHE_NONE`. Regions cached by a thread
+    /// cache are not discarded when their arena is reset or destroyed, so allocations from arenas
+    /// which will be reset or destroyed must bypass the thread cache.
+    pub fn flags(&self) -> c_int {
+        jemalloc_sys::MALLOCX_ARENA(self.0 as usize) | jemalloc_sys::MALLOCX_TCACHE_NONE()
+    }
+
+    /// Discards all of the arena's allocations.
+    ///
+    /// Only arenas created with [`create`] can be reset; an [`Error::Unsupported`] error is
+    /// returned for the automatically managed arenas.
+    ///
+    /// This corresponds to `arena.<i>.reset` in jemalloc's API.
+    ///
+    /// # Safety
+    ///
+    /// Every allocation made in the arena is freed, so no pointer to memory allocated from it may
+    /// be used afterwards. Allocations must not have been made through a thread cache; see
+    /// [`ArenaId::flags`].
+    ///
+    /// [`ArenaId::flags`]: #method.flags
+    /// [`create`]: fn.create.html
+    /// [`Error::Unsupported`]: ../enum.Error.html#variant.Unsupported
+    pub unsafe fn reset(&self) -> Result<()> {
+        Reset::new()?.reset(*self)
+    }
+
+    /// Destroys the arena, discarding all of its allocations.
+    ///
+    /// The arena's statistics are merged into those of the [`DESTROYED`] pseudo-arena, and its
+    /// index may be reused by a later call to [`create`]. Only arenas created with [`create`] and
+    /// not bound to any thread can be destroyed; an [`Error::Unsupported`] error is returned
+    /// otherwise.
+    ///
+    /// This corresponds to `arena.<i>.destroy` in jemalloc's API.
+    ///
+    /// # Safety
+    ///
+    /// Every allocation made in the arena is freed, so no pointer to memory allocated from it may
+    /// be used afterwards. Allocations must not have been made through a thread cache; see
+    /// [`ArenaId::flags`].
+    ///
+    /// [`ArenaId::flags`]: #method.flags
+    /// [`DESTROYED`]: constant.DESTROYED.html
+    /// [`create`]: fn.create.html
+    /// [`Error::Unsupported`]: ../enum.Error.html#variant.Unsupported
+    pub unsafe fn destroy(self) -> Result<()> {
+        Destroy::new()?.destroy(self)
+    }
+}
+
+impl From<c_uint> for ArenaId {
+    fn from(index: c_uint) -> ArenaId {
+        ArenaId(index)
+    }
+}
+
+impl From<ArenaId> for c_uint {
+    fn from(arena: ArenaId) -> c_uint {
+        arena.0
+    }
+}
+
+const CREATE: &[u8] = b"arenas.create\0";
+
+/// Creates a new arena, returning its index.
+///
+/// This corresponds to `arenas.create` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+/// extern crate jemalloc_sys;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let arena = jemalloc_ctl::arenas::create().unwrap();
+///
+///     unsafe {
+///         let ptr = jemalloc_sys::mallocx(1024, arena.flags());
+///         assert!(!ptr.is_null());
+///         jemalloc_sys::dallocx(ptr, arena.flags());
+///
+///         arena.destroy().unwrap();
+///     }
+/// }
+/// ```
+pub fn create() -> Result<ArenaId> {
+    unsafe { get(CREATE).map(ArenaId) }
+}
+
+/// A type providing access to the creation of new arenas.
+///
+/// This corresponds to `arenas.create` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::Create;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let create = Create::new().unwrap();
+///
+///     let a = create.create().unwrap();
+///     let b = create.create().unwrap();
+///     assert!(a != b);
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Create(Mib<c_uint, [usize; 2]>);
+
+impl Create {
+    /// Returns a new `Create`.
+    pub fn new() -> Result<Create> {
+        unsafe { Mib::new(CREATE).map(Create) }
+    }
+
+    /// Creates a new arena, returning its index.
+    pub fn create(&self) -> Result<ArenaId> {
+        self.0.read().map(ArenaId)
+    }
+}
+
+/// Creates a new arena whose extents are managed by custom hooks, returning its index.
+///
+/// The hooks are never dropped, since jemalloc requires them to remain valid for as long as any
+/// arena uses them.
+///
+/// This corresponds to `arenas.create` in jemalloc's API.
+///
+/// # Examples
+///
+/// See the [`extent_hooks`] module.
+///
+/// [`extent_hooks`]: ../extent_hooks/index.html
+pub fn create_with_hooks<H>(hooks: H) -> Result<ArenaId>
+where
+    H: ExtentHooks,
+{
+    unsafe {
+        let raw = extent_hooks::into_raw(hooks);
+        match get_set(CREATE, raw) {
+            Ok(arena) => Ok(ArenaId(arena)),
+            Err(e) => {
+                extent_hooks::free_raw::<H>(raw);
+                Err(e)
+            }
+        }
+    }
+}
+
+const EXTENT_HOOKS: &[u8] = b"arena.0.extent_hooks\0";
+
+/// Replaces the hooks managing the extents of an arena.
+///
+/// Like [`create_with_hooks`], the hooks are never dropped.
+///
+/// This corresponds to `arena.<i>.extent_hooks` in jemalloc's API.
+///
+/// # Safety
+///
+/// Extents the arena already holds will be passed to the new hooks, which must be prepared to
+/// deallocate, commit, purge, split and merge them.
+///
+/// [`create_with_hooks`]: fn.create_with_hooks.html
+pub unsafe fn set_extent_hooks<H>(arena: c_uint, hooks: H) -> Result<()>
+where
+    H: ExtentHooks,
+{
+    let mib = Mib::<*mut extent_hooks_t, [usize; 3]>::new(EXTENT_HOOKS)?;
+    let raw = extent_hooks::into_raw(hooks);
+    let r = mib.with_index(0, arena as usize).write(raw);
+    if r.is_err() {
+        extent_hooks::free_raw::<H>(raw);
+    }
+    r
+}
+
+const RESET: &[u8] = b"arena.0.reset\0";
+
+/// A type providing access to the reset of arenas.
+///
+/// See [`ArenaId::reset`] for more information.
+///
+/// This corresponds to `arena.<i>.reset` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, Reset};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let reset = Reset::new().unwrap();
+///     let arena = arenas::create().unwrap();
+///
+///     unsafe {
+///         reset.reset(arena).unwrap();
+///     }
+/// }
+/// ```
+///
+/// [`ArenaId::reset`]: struct.ArenaId.html#method.reset
+#[derive(Copy, Clone)]
+pub struct Reset(Mib<(), [usize; 3]>);
+
+impl Reset {
+    /// Returns a new `Reset`.
+    pub fn new() -> Result<Reset> {
+        unsafe { Mib::new(RESET).map(Reset) }
+    }
+
+    /// Discards all of the arena's allocations.
+    ///
+    /// # Safety
+    ///
+    /// See [`ArenaId::reset`].
+    ///
+    /// [`ArenaId::reset`]: struct.ArenaId.html#method.reset
+    pub unsafe fn reset(&self, arena: ArenaId) -> Result<()> {
+        self.0.with_index(0, arena.0 as usize).run()
+    }
+}
+
+const DESTROY: &[u8] = b"arena.0.destroy\0";
+
+/// A type providing access to the destruction of arenas.
+///
+/// See [`ArenaId::destroy`] for more information.
+///
+/// This corresponds to `arena.<i>.destroy` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, Destroy};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let destroy = Destroy::new().unwrap();
+///     let arena = arenas::create().unwrap();
+///
+///     unsafe {
+///         destroy.destroy(arena).unwrap();
+///     }
+/// }
+/// ```
+///
+/// [`ArenaId::destroy`]: struct.ArenaId.html#method.destroy
+#[derive(Copy, Clone)]
+pub struct Destroy(Mib<(), [usize; 3]>);
+
+impl Destroy {
+    /// Returns a new `Destroy`.
+    pub fn new() -> Result<Destroy> {
+        unsafe { Mib::new(DESTROY).map(Destroy) }
+    }
+
+    /// Destroys the arena, discarding all of its allocations.
+    ///
+    /// # Safety
+    ///
+    /// See [`ArenaId::destroy`].
+    ///
+    /// [`ArenaId::destroy`]: struct.ArenaId.html#method.destroy
+    pub unsafe fn destroy(&self, arena: ArenaId) -> Result<()> {
+        self.0.with_index(0, arena.0 as usize).run()
+    }
+}
+
+const LOOKUP: &[u8] = b"arenas.lookup\0";
+
+/// Returns the arena which owns an allocation.
+///
+/// An [`Error::InvalidValue`] error is returned if the pointer is null, or if jemalloc has no
+/// extent registered at its address.
+///
+/// This corresponds to `arenas.lookup` in jemalloc's API.
+///
+/// # Safety
+///
+/// The pointer must be null, or a pointer returned by one of jemalloc's allocation functions.
+/// jemalloc does not check whether it owns the pointer before looking it up, and will crash on
+/// pointers to other memory such as the stack, static data, or allocations made by another
+/// allocator. Pointers to allocations which have since been freed are permitted.
+///
+/// Pointers into the middle of an allocation are not supported. jemalloc only registers the first
+/// and last pages of large allocations, so looking up an address between them may return an
+/// error, the arena owning another allocation, or crash.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+/// extern crate jemalloc_sys;
+///
+/// use jemalloc_ctl::arenas;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let arena = arenas::create().unwrap();
+///
+///     unsafe {
+///         let ptr = jemalloc_sys::mallocx(1024, arena.flags());
+///         assert_eq!(arenas::lookup(ptr as *const u8).unwrap(), arena);
+///         jemalloc_sys::dallocx(ptr, arena.flags());
+///     }
+/// }
+/// ```
+///
+/// [`Error::InvalidValue`]: ../enum.Error.html#variant.InvalidValue
+pub unsafe fn lookup(ptr: *const u8) -> Result<ArenaId> {
+    Lookup::new()?.lookup(ptr)
+}
+
+/// A type providing access to the arena which owns an allocation.
+///
+/// See [`lookup`] for more information.
+///
+/// This corresponds to `arenas.lookup` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::{arenas, thread};
+/// use jemalloc_ctl::arenas::Lookup;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let lookup = Lookup::new().unwrap();
+///     let arena = arenas::create().unwrap();
+///
+///     let _guard = thread::bind_arena(arena).unwrap();
+///     let buf = vec![0u8; 1024 * 1024];
+///     assert_eq!(unsafe { lookup.lookup(buf.as_ptr()).unwrap() }, arena);
+/// }
+/// ```
+///
+/// [`lookup`]: fn.lookup.html
+#[derive(Copy, Clone)]
+pub struct Lookup(Mib<c_uint, [usize; 2]>);
+
+impl Lookup {
+    /// Returns a new `Lookup`.
+    pub fn new() -> Result<Lookup> {
+        unsafe { Mib::new(LOOKUP).map(Lookup) }
+    }
+
+    /// Returns the arena which owns an allocation.
+    ///
+    /// # Safety
+    ///
+    /// See [`lookup`].
+    ///
+    /// [`lookup`]: fn.lookup.html
+    pub unsafe fn lookup(&self, ptr: *const u8) -> Result<ArenaId> {
+        if ptr.is_null() {
+            return Err(Error::InvalidValue {
+                name: name_string(LOOKUP),
+            });
+        }
+        get_set_mib(self.0.as_slice(), ptr, &|| name_string(LOOKUP)).map(ArenaId)
+    }
+}
+
+const PURGE: &[u8] = b"arena.0.purge\0";
+
+/// Purges all unused dirty and muzzy pages of the arena, returning them to the operating system.
+///
+/// The [`ALL`] pseudo-index purges every arena.
+///
+/// This corresponds to `arena.<i>.purge` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     drop(vec![0u8; 16 * 1024 * 1024]);
+///     arenas::purge(arenas::ALL).unwrap();
+/// }
+/// ```
+///
+/// [`ALL`]: constant.ALL.html
+pub fn purge(arena: c_uint) -> Result<()> {
+    Purge::new()?.purge(arena)
+}
+
+/// A type providing access to the purging of unused pages.
+///
+/// This corresponds to `arena.<i>.purge` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, Purge};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let purge = Purge::new().unwrap();
+///
+///     for arena in 0..arenas::narenas().unwrap() {
+///         purge.purge(arena).unwrap();
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Purge(Mib<(), [usize; 3]>);
+
+impl Purge {
+    /// Returns a new `Purge`.
+    pub fn new() -> Result<Purge> {
+        unsafe { Mib::new(PURGE).map(Purge) }
+    }
+
+    /// Purges all unused dirty and muzzy pages of the arena.
+    pub fn purge(&self, arena: c_uint) -> Result<()> {
+        self.0.with_index(0, arena as usize).run()
+    }
+}
+
+const DECAY: &[u8] = b"arena.0.decay\0";
+
+/// Purges the arena's unused dirty and muzzy pages which are due to be purged according to its
+/// decay times.
+///
+/// Unlike [`purge`], pages which only recently became unused are kept. The [`ALL`]
+/// pseudo-index applies to every arena.
+///
+/// This corresponds to `arena.<i>.decay` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     arenas::decay(arenas::ALL).unwrap();
+/// }
+/// ```
+///
+/// [`purge`]: fn.purge.html
+/// [`ALL`]: constant.ALL.html
+pub fn decay(arena: c_uint) -> Result<()> {
+    Decay::new()?.decay(arena)
+}
+
+/// A type providing access to decay-based purging of unused pages.
+///
+/// This corresponds to `arena.<i>.decay` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, Decay};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let decay = Decay::new().unwrap();
+///
+///     decay.decay(arenas::ALL).unwrap();
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Decay(Mib<(), [usize; 3]>);
+
+impl Decay {
+    /// Returns a new `Decay`.
+    pub fn new() -> Result<Decay> {
+        unsafe { Mib::new(DECAY).map(Decay) }
+    }
+
+    /// Purges the arena's unused dirty and muzzy pages which are due to be purged.
+    pub fn decay(&self, arena: c_uint) -> Result<()> {
+        self.0.with_index(0, arena as usize).run()
+    }
+}
+
+const SSIZE_MAX: ssize_t = (!0usize >> 1) as ssize_t;
+
+/// The time it takes for unused pages to be purged.
+///
+/// jemalloc purges unused pages gradually, following a sigmoidal decay curve which starts and ends
+/// with zero purge rate. The decay time is the time from when a page becomes unused until it is
+/// purged.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DecayTime {
+    /// Unused pages are purged immediately.
+    Immediate,
+
+    /// Unused pages are never purged.
+    Disabled,
+
+    /// Unused pages are purged over the specified number of milliseconds.
+    ///
+    /// jemalloc reports a decay time of 0 as `Immediate`.
+    Millis(u64),
+}
+
+impl DecayTime {
+    pub(crate) fn from_raw(ms: ssize_t) -> DecayTime {
+        match ms {
+            0 => DecayTime::Immediate,
+            ms if ms < 0 => DecayTime::Disabled,
+            ms => DecayTime::Millis(ms as u64),
+        }
+    }
+
+    // Out of range values are saturated to a positive value jemalloc rejects, rather than wrapping
+    // to a negative one which could be mistaken for -1.
+    pub(crate) fn to_raw(self) -> ssize_t {
+        match self {
+            DecayTime::Immediate => 0,
+            DecayTime::Disabled => -1,
+            DecayTime::Millis(ms) if ms > SSIZE_MAX as u64 => SSIZE_MAX,
+            DecayTime::Millis(ms) => ms as ssize_t,
+        }
+    }
+}
+
+const ARENA_DIRTY_DECAY_MS: &[u8] = b"arena.0.dirty_decay_ms\0";
+
+/// Returns the time it takes for the arena's unused dirty pages to be purged.
+///
+/// This corresponds to `arena.<i>.dirty_decay_ms` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, DecayTime};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let arena = arenas::create().unwrap();
+///     arenas::set_dirty_decay_ms(arena.index(), DecayTime::Millis(5000)).unwrap();
+///     assert_eq!(arenas::dirty_decay_ms(arena.index()).unwrap(), DecayTime::Millis(5000));
+/// }
+/// ```
+pub fn dirty_decay_ms(arena: c_uint) -> Result<DecayTime> {
+    DirtyDecayMs::new()?.get(arena)
+}
+
+/// Sets the time it takes for the arena's unused dirty pages to be purged.
+///
+/// This corresponds to `arena.<i>.dirty_decay_ms` in jemalloc's API.
+pub fn set_dirty_decay_ms(arena: c_uint, decay: DecayTime) -> Result<()> {
+    DirtyDecayMs::new()?.set(arena, decay)
+}
+
+/// A type providing access to the time it takes for the arena's unused dirty pages to be purged.
+///
+/// This corresponds to `arena.<i>.dirty_decay_ms` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, DirtyDecayMs};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let dirty_decay_ms = DirtyDecayMs::new().unwrap();
+///
+///     for arena in 0..arenas::narenas().unwrap() {
+///         if let Ok(decay) = dirty_decay_ms.get(arena) {
+///             println!("arena {}: {:?}", arena, decay);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct DirtyDecayMs(Mib<ssize_t, [usize; 3]>);
+
+impl DirtyDecayMs {
+    /// Returns a new `DirtyDecayMs`.
+    pub fn new() -> Result<DirtyDecayMs> {
+        unsafe { Mib::new(ARENA_DIRTY_DECAY_MS).map(DirtyDecayMs) }
+    }
+
+    /// Returns the time it takes for the arena's unused dirty pages to be purged.
+    pub fn get(&self, arena: c_uint) -> Result<DecayTime> {
+        self.0
+            .with_index(0, arena as usize)
+            .read()
+            .map(DecayTime::from_raw)
+    }
+
+    /// Sets the time it takes for the arena's unused dirty pages to be purged.
+    pub fn set(&self, arena: c_uint, decay: DecayTime) -> Result<()> {
+        self.0.with_index(0, arena as usize).write(decay.to_raw())
+    }
+}
+
+const ARENA_MUZZY_DECAY_MS: &[u8] = b"arena.0.muzzy_decay_ms\0";
+
+/// Returns the time it takes for the arena's unused muzzy pages to be purged.
+///
+/// This corresponds to `arena.<i>.muzzy_decay_ms` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, DecayTime};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let arena = arenas::create().unwrap();
+///     arenas::set_muzzy_decay_ms(arena.index(), DecayTime::Millis(5000)).unwrap();
+///     assert_eq!(arenas::muzzy_decay_ms(arena.index()).unwrap(), DecayTime::Millis(5000));
+/// }
+/// ```
+pub fn muzzy_decay_ms(arena: c_uint) -> Result<DecayTime> {
+    MuzzyDecayMs::new()?.get(arena)
+}
+
+/// Sets the time it takes for the arena's unused muzzy pages to be purged.
+///
+/// This corresponds to `arena.<i>.muzzy_decay_ms` in jemalloc's API.
+pub fn set_muzzy_decay_ms(arena: c_uint, decay: DecayTime) -> Result<()> {
+    MuzzyDecayMs::new()?.set(arena, decay)
+}
+
+/// A type providing access to the time it takes for the arena's unused muzzy pages to be purged.
+///
+/// This corresponds to `arena.<i>.muzzy_decay_ms` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, MuzzyDecayMs};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let muzzy_decay_ms = MuzzyDecayMs::new().unwrap();
+///
+///     for arena in 0..arenas::narenas().unwrap() {
+///         if let Ok(decay) = muzzy_decay_ms.get(arena) {
+///             println!("arena {}: {:?}", arena, decay);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct MuzzyDecayMs(Mib<ssize_t, [usize; 3]>);
+
+impl MuzzyDecayMs {
+    /// Returns a new `MuzzyDecayMs`.
+    pub fn new() -> Result<MuzzyDecayMs> {
+        unsafe { Mib::new(ARENA_MUZZY_DECAY_MS).map(MuzzyDecayMs) }
+    }
+
+    /// Returns the time it takes for the arena's unused muzzy pages to be purged.
+    pub fn get(&self, arena: c_uint) -> Result<DecayTime> {
+        self.0
+            .with_index(0, arena as usize)
+            .read()
+            .map(DecayTime::from_raw)
+    }
+
+    /// Sets the time it takes for the arena's unused muzzy pages to be purged.
+    pub fn set(&self, arena: c_uint, decay: DecayTime) -> Result<()> {
+        self.0.with_index(0, arena as usize).write(decay.to_raw())
+    }
+}
+
+const ARENAS_DIRTY_DECAY_MS: &[u8] = b"arenas.dirty_decay_ms\0";
+
+/// Returns the dirty decay time used for newly created arenas.
+///
+/// This corresponds to `arenas.dirty_decay_ms` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, DecayTime};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     arenas::set_default_dirty_decay_ms(DecayTime::Disabled).unwrap();
+///     assert_eq!(arenas::default_dirty_decay_ms().unwrap(), DecayTime::Disabled);
+/// }
+/// ```
+pub fn default_dirty_decay_ms() -> Result<DecayTime> {
+    DefaultDirtyDecayMs::new()?.get()
+}
+
+/// Sets the dirty decay time used for newly created arenas.
+///
+/// This corresponds to `arenas.dirty_decay_ms` in jemalloc's API.
+pub fn set_default_dirty_decay_ms(decay: DecayTime) -> Result<()> {
+    DefaultDirtyDecayMs::new()?.set(decay)
+}
+
+/// A type providing access to the dirty decay time used for newly created arenas.
+///
+/// This corresponds to `arenas.dirty_decay_ms` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::DefaultDirtyDecayMs;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let default_dirty_decay_ms = DefaultDirtyDecayMs::new().unwrap();
+///
+///     println!("dirty decay time: {:?}", default_dirty_decay_ms.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct DefaultDirtyDecayMs(Mib<ssize_t, [usize; 2]>);
+
+impl DefaultDirtyDecayMs {
+    /// Returns a new `DefaultDirtyDecayMs`.
+    pub fn new() -> Result<DefaultDirtyDecayMs> {
+        unsafe { Mib::new(ARENAS_DIRTY_DECAY_MS).map(DefaultDirtyDecayMs) }
+    }
+
+    /// Returns the dirty decay time used for newly created arenas.
+    pub fn get(&self) -> Result<DecayTime> {
+        self.0.read().map(DecayTime::from_raw)
+    }
+
+    /// Sets the dirty decay time used for newly created arenas.
+    pub fn set(&self, decay: DecayTime) -> Result<()> {
+        self.0.write(decay.to_raw())
+    }
+}
+
+const ARENAS_MUZZY_DECAY_MS: &[u8] = b"arenas.muzzy_decay_ms\0";
+
+/// Returns the muzzy decay time used for newly created arenas.
+///
+/// This corresponds to `arenas.muzzy_decay_ms` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, DecayTime};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     arenas::set_default_muzzy_decay_ms(DecayTime::Disabled).unwrap();
+///     assert_eq!(arenas::default_muzzy_decay_ms().unwrap(), DecayTime::Disabled);
+/// }
+/// ```
+pub fn default_muzzy_decay_ms() -> Result<DecayTime> {
+    DefaultMuzzyDecayMs::new()?.get()
+}
+
+/// Sets the muzzy decay time used for newly created arenas.
+///
+/// This corresponds to `arenas.muzzy_decay_ms` in jemalloc's API.
+pub fn set_default_muzzy_decay_ms(decay: DecayTime) -> Result<()> {
+    DefaultMuzzyDecayMs::new()?.set(decay)
+}
+
+/// A type providing access to the muzzy decay time used for newly created arenas.
+///
+/// This corresponds to `arenas.muzzy_decay_ms` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::DefaultMuzzyDecayMs;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let default_muzzy_decay_ms = DefaultMuzzyDecayMs::new().unwrap();
+///
+///     println!("muzzy decay time: {:?}", default_muzzy_decay_ms.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct DefaultMuzzyDecayMs(Mib<ssize_t, [usize; 2]>);
+
+impl DefaultMuzzyDecayMs {
+    /// Returns a new `DefaultMuzzyDecayMs`.
+    pub fn new() -> Result<DefaultMuzzyDecayMs> {
+        unsafe { Mib::new(ARENAS_MUZZY_DECAY_MS).map(DefaultMuzzyDecayMs) }
+    }
+
+    /// Returns the muzzy decay time used for newly created arenas.
+    pub fn get(&self) -> Result<DecayTime> {
+        self.0.read().map(DecayTime::from_raw)
+    }
+
+    /// Sets the muzzy decay time used for newly created arenas.
+    pub fn set(&self, decay: DecayTime) -> Result<()> {
+        self.0.write(decay.to_raw())
+    }
+}
+
+const QUANTUM: &[u8] = b"arenas.quantum\0";
+
+/// Returns the quantum, which is the alignment and spacing of the smallest size classes.
+///
+/// This corresponds to `arenas.quantum` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("quantum: {} bytes", jemalloc_ctl::arenas::quantum().unwrap());
+/// }
+/// ```
+pub fn quantum() -> Result<usize> {
+    unsafe { get(QUANTUM) }
+}
+
+/// A type providing access to the quantum, which is the alignment and spacing of the smallest size classes.
+///
+/// This corresponds to `arenas.quantum` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::Quantum;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let quantum = Quantum::new().unwrap();
+///
+///     println!("quantum: {} bytes", quantum.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Quantum(Mib<usize, [usize; 2]>);
+
+impl Quantum {
+    /// Returns a new `Quantum`.
+    pub fn new() -> Result<Quantum> {
+        unsafe { Mib::new(QUANTUM).map(Quantum) }
+    }
+
+    /// Returns the quantum, which is the alignment and spacing of the smallest size classes.
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
+    }
+}
+
+const PAGE: &[u8] = b"arenas.page\0";
+
+/// Returns the page size.
+///
+/// This corresponds to `arenas.page` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("page size: {} bytes", jemalloc_ctl::arenas::page().unwrap());
+/// }
+/// ```
+pub fn page() -> Result<usize> {
+    unsafe { get(PAGE) }
+}
+
+/// A type providing access to the page size.
+///
+/// This corresponds to `arenas.page` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::Page;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let page = Page::new().unwrap();
+///
+///     println!("page size: {} bytes", page.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Page(Mib<usize, [usize; 2]>);
+
+impl Page {
+    /// Returns a new `Page`.
+    pub fn new() -> Result<Page> {
+        unsafe { Mib::new(PAGE).map(Page) }
+    }
+
+    /// Returns the page size.
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
+    }
+}
+
+const TCACHE_MAX: &[u8] = b"arenas.tcache_max\0";
+
+/// Returns the largest size class cached by thread caches.
+///
+/// This corresponds to `arenas.tcache_max` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("largest cached size: {} bytes", jemalloc_ctl::arenas::tcache_max().unwrap());
+/// }
+/// ```
+pub fn tcache_max() -> Result<usize> {
+    unsafe { get(TCACHE_MAX) }
+}
+
+/// A type providing access to the largest size class cached by thread caches.
+///
+/// This corresponds to `arenas.tcache_max` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::TcacheMax;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let tcache_max = TcacheMax::new().unwrap();
+///
+///     println!("largest cached size: {} bytes", tcache_max.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct TcacheMax(Mib<usize, [usize; 2]>);
+
+impl TcacheMax {
+    /// Returns a new `TcacheMax`.
+    pub fn new() -> Result<TcacheMax> {
+        unsafe { Mib::new(TCACHE_MAX).map(TcacheMax) }
+    }
+
+    /// Returns the largest size class cached by thread caches.
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
+    }
+}
+
+const NBINS: &[u8] = b"arenas.nbins\0";
+
+/// Returns the number of bin size classes.
+///
+/// This corresponds to `arenas.nbins` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("number of bins: {}", jemalloc_ctl::arenas::nbins().unwrap());
+/// }
+/// ```
+pub fn nbins() -> Result<c_uint> {
+    unsafe { get(NBINS) }
+}
+
+/// A type providing access to the number of bin size classes.
+///
+/// This corresponds to `arenas.nbins` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::NBins;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let nbins = NBins::new().unwrap();
+///
+///     println!("number of bins: {}", nbins.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct NBins(Mib<c_uint, [usize; 2]>);
+
+impl NBins {
+    /// Returns a new `NBins`.
+    pub fn new() -> Result<NBins> {
+        unsafe { Mib::new(NBINS).map(NBins) }
+    }
+
+    /// Returns the number of bin size classes.
+    pub fn get(&self) -> Result<c_uint> {
+        self.0.read()
+    }
+}
+
+const NHBINS: &[u8] = b"arenas.nhbins\0";
+
+/// Returns the number of size classes cached by thread caches.
+///
+/// This corresponds to `arenas.nhbins` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("number of thread cache bins: {}", jemalloc_ctl::arenas::nhbins().unwrap());
+/// }
+/// ```
+pub fn nhbins() -> Result<c_uint> {
+    unsafe { get(NHBINS) }
+}
+
+/// A type providing access to the number of size classes cached by thread caches.
+///
+/// This corresponds to `arenas.nhbins` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::NHBins;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let nhbins = NHBins::new().unwrap();
+///
+///     println!("number of thread cache bins: {}", nhbins.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct NHBins(Mib<c_uint, [usize; 2]>);
+
+impl NHBins {
+    /// Returns a new `NHBins`.
+    pub fn new() -> Result<NHBins> {
+        unsafe { Mib::new(NHBINS).map(NHBins) }
+    }
+
+    /// Returns the number of size classes cached by thread caches.
+    pub fn get(&self) -> Result<c_uint> {
+        self.0.read()
+    }
+}
+
+const NLEXTENTS: &[u8] = b"arenas.nlextents\0";
+
+/// Returns the number of large size classes.
+///
+/// This corresponds to `arenas.nlextents` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("number of large size classes: {}", jemalloc_ctl::arenas::nlextents().unwrap());
+/// }
+/// ```
+pub fn nlextents() -> Result<c_uint> {
+    unsafe { get(NLEXTENTS) }
+}
+
+/// A type providing access to the number of large size classes.
+///
+/// This corresponds to `arenas.nlextents` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::NLExtents;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let nlextents = NLExtents::new().unwrap();
+///
+///     println!("number of large size classes: {}", nlextents.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct NLExtents(Mib<c_uint, [usize; 2]>);
+
+impl NLExtents {
+    /// Returns a new `NLExtents`.
+    pub fn new() -> Result<NLExtents> {
+        unsafe { Mib::new(NLEXTENTS).map(NLExtents) }
+    }
+
+    /// Returns the number of large size classes.
+    pub fn get(&self) -> Result<c_uint> {
+        self.0.read()
+    }
+}
+
+const BIN_SIZE: &[u8] = b"arenas.bin.0.size\0";
+const BIN_NREGS: &[u8] = b"arenas.bin.0.nregs\0";
+const BIN_SLAB_SIZE: &[u8] = b"arenas.bin.0.slab_size\0";
+const BIN_NSHARDS: &[u8] = b"arenas.bin.0.nshards\0";
+
+/// The layout of a bin size class.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BinInfo {
+    /// The size of the regions in the bin.
+    pub size: usize,
+
+    /// The number of regions in each slab.
+    pub nregs: u32,
+
+    /// The size of each slab.
+    pub slab_size: usize,
+
+    /// The number of shards the bin is split into in each arena.
+    ///
+    /// This is only available in jemalloc 5.2 and later.
+    pub nshards: Option<u32>,
+}
+
+/// Returns the layout of a bin size class.
+///
+/// Bins are indexed from 0 up to (but not including) the value returned by [`nbins`], in order of
+/// increasing size.
+///
+/// This corresponds to `arenas.bin.<i>.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let bin = arenas::bin(0).unwrap();
+///     assert_eq!(bin.slab_size, bin.size * bin.nregs as usize);
+/// }
+/// ```
+///
+/// [`nbins`]: fn.nbins.html
+pub fn bin(bin: c_uint) -> Result<BinInfo> {
+    Bin::new()?.get(bin)
+}
+
+/// A type providing access to the layout of bin size classes.
+///
+/// This corresponds to `arenas.bin.<i>.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, Bin};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let bin = Bin::new().unwrap();
+///
+///     for i in 0..arenas::nbins().unwrap() {
+///         let info = bin.get(i).unwrap();
+///         println!("{} byte regions, {} per slab", info.size, info.nregs);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Bin {
+    size: Mib<usize, [usize; 4]>,
+    nregs: Mib<u32, [usize; 4]>,
+    slab_size: Mib<usize, [usize; 4]>,
+    nshards: Option<Mib<u32, [usize; 4]>>,
+}
+
+impl Bin {
+    /// Returns a new `Bin`.
+    pub fn new() -> Result<Bin> {
+        unsafe {
+            Ok(Bin {
+                size: Mib::new(BIN_SIZE)?,
+                nregs: Mib::new(BIN_NREGS)?,
+                slab_size: Mib::new(BIN_SLAB_SIZE)?,
+                nshards: optional(Mib::new(BIN_NSHARDS))?,
+            })
+        }
+    }
+
+    /// Returns the layout of a bin size class.
+    pub fn get(&self, bin: c_uint) -> Result<BinInfo> {
+        let bin = bin as usize;
+        Ok(BinInfo {
+            size: self.size.with_index(0, bin).read()?,
+            nregs: self.nregs.with_index(0, bin).read()?,
+            slab_size: self.slab_size.with_index(0, bin).read()?,
+            nshards: match self.nshards {
+                Some(mib) => Some(mib.with_index(0, bin).read()?),
+                None => None,
+            },
+        })
+    }
+}
+
+const LEXTENT_SIZE: &[u8] = b"arenas.lextent.0.size\0";
+
+/// Returns the size of a large size class.
+///
+/// Large size classes are indexed from 0 up to (but not including) the value returned by
+/// [`nlextents`], in order of increasing size.
+///
+/// This corresponds to `arenas.lextent.<i>.size` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let smallest = arenas::lextent_size(0).unwrap();
+///     let nbins = arenas::nbins().unwrap();
+///     assert!(arenas::bin(nbins - 1).unwrap().size < smallest);
+/// }
+/// ```
+///
+/// [`nlextents`]: fn.nlextents.html
+pub fn lextent_size(lextent: c_uint) -> Result<usize> {
+    LExtentSize::new()?.get(lextent)
+}
+
+/// A type providing access to the sizes of large size classes.
+///
+/// This corresponds to `arenas.lextent.<i>.size` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, LExtentSize};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let lextent_size = LExtentSize::new().unwrap();
+///
+///     for i in 0..10 {
+///         println!("{} bytes", lextent_size.get(i).unwrap());
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct LExtentSize(Mib<usize, [usize; 4]>);
+
+impl LExtentSize {
+    /// Returns a new `LExtentSize`.
+    pub fn new() -> Result<LExtentSize> {
+        unsafe { Mib::new(LEXTENT_SIZE).map(LExtentSize) }
+    }
+
+    /// Returns the size of a large size class.
+    pub fn get(&self, lextent: c_uint) -> Result<usize> {
+        self.0.with_index(0, lextent as usize).read()
+    }
+}
+
+/// The size class serving an allocation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SizeClass {
+    /// A bin size class, whose allocations are carved out of slabs.
+    Small {
+        /// The index of the bin.
+        bin: c_uint,
+        /// The size of the bin's regions.
+        size: usize,
+    },
+
+    /// A large size class, whose allocations each have their own extent.
+    Large {
+        /// The index of the large size class.
+        lextent: c_uint,
+        /// The size of the size class.
+        size: usize,
+    },
+}
+
+impl SizeClass {
+    /// Returns the size of the size class.
+    ///
+    /// This is the usable size of allocations it serves.
+    pub fn size(&self) -> usize {
+        match *self {
+            SizeClass::Small { size, .. } | SizeClass::Large { size, .. } => size,
+        }
+    }
+}
+
+/// A snapshot of jemalloc's size classes.
+///
+/// The size classes are fixed when jemalloc is built, so the snapshot never becomes stale.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::SizeClasses;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let classes = SizeClasses::new().unwrap();
+///
+///     // a 4097 byte struct wastes nearly 1KiB per allocation
+///     println!("{:?}", classes.class_for(4097));
+///     println!("{:?} bytes wasted", classes.waste_for(4097));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SizeClasses {
+    quantum: usize,
+    page: usize,
+    tcache_max: usize,
+    bins: Vec<BinInfo>,
+    lextents: Vec<usize>,
+}
+
+impl SizeClasses {
+    /// Reads the size classes from jemalloc.
+    pub fn new() -> Result<SizeClasses> {
+        let bin = Bin::new()?;
+        let bins = (0..nbins()?)
+            .map(|i| bin.get(i))
+            .collect::<Result<Vec<_>>>()?;
+        let lextent_size = LExtentSize::new()?;
+        let lextents = (0..nlextents()?)
+            .map(|i| lextent_size.get(i))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(SizeClasses {
+            quantum: quantum()?,
+            page: page()?,
+            tcache_max: tcache_max()?,
+            bins,
+            lextents,
+        })
+    }
+
+    /// Returns the quantum.
+    pub fn quantum(&self) -> usize {
+        self.quantum
+    }
+
+    /// Returns the page size.
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    /// Returns the largest size class cached by thread caches.
+    pub fn tcache_max(&self) -> usize {
+        self.tcache_max
+    }
+
+    /// Returns the bin size classes, in order of increasing size.
+    pub fn bins(&self) -> &[BinInfo] {
+        &self.bins
+    }
+
+    /// Returns the sizes of the large size classes, in increasing order.
+    pub fn lextents(&self) -> &[usize] {
+        &self.lextents
+    }
+
+    /// Returns the size class which serves an allocation of `size` bytes.
+    ///
+    /// Alignment requirements are not taken into account. A zero-sized allocation is served by the
+    /// smallest size class. `None` is returned if `size` is larger than the largest size class.
+    pub fn class_for(&self, size: usize) -> Option<SizeClass> {
+        let i = match self.bins.binary_search_by(|bin| bin.size.cmp(&size)) {
+            Ok(i) | Err(i) => i,
+        };
+        if let Some(bin) = self.bins.get(i) {
+            return Some(SizeClass::Small {
+                bin: i as c_uint,
+                size: bin.size,
+            });
+        }
+
+        let i = match self.lextents.binary_search(&size) {
+            Ok(i) | Err(i) => i,
+        };
+        self.lextents.get(i).map(|&lextent| SizeClass::Large {
+            lextent: i as c_uint,
+            size: lextent,
+        })
+    }
+
+    /// Returns the number of bytes wasted by rounding an allocation of `size` bytes up to its size
+    /// class.
+    ///
+    /// `None` is returned if `size` is larger than the largest size class.
+    pub fn waste_for(&self, size: usize) -> Option<usize> {
+        self.class_for(size).map(|class| class.size() - size)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use stats;
+    use std::ptr;
+
+    #[test]
+    fn create_allocate_destroy() {
+        let arena = create().unwrap();
+        assert!(arena.index() < narenas().unwrap());
+
+        unsafe {
+            let ptr = jemalloc_sys::mallocx(4096, arena.flags()) as *mut u8;
+            assert!(!ptr.is_null());
+            *ptr = 1;
+            ::epoch().unwrap();
+            assert!(stats::arenas::small(arena.index()).unwrap().nmalloc > 0);
+
+            arena.destroy().unwrap();
+        }
+
+        ::epoch().unwrap();
+        match stats::arenas::pactive(arena.index()) {
+            Err(Error::UnknownName { .. }) => {}
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn reset_keeps_arena() {
+        let arena = create().unwrap();
+
+        unsafe {
+            let ptr = jemalloc_sys::mallocx(1024 * 1024, arena.flags());
+            assert!(!ptr.is_null());
+            arena.reset().unwrap();
+
+            let ptr = jemalloc_sys::mallocx(1024, arena.flags());
+            assert!(!ptr.is_null());
+            jemalloc_sys::dallocx(ptr, arena.flags());
+
+            arena.destroy().unwrap();
+        }
+    }
+
+    #[test]
+    fn reset_automatic_arena() {
+        match unsafe { ArenaId::from(0).reset() } {
+            Err(Error::Unsupported { .. }) => {}
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn purge_releases_dirty_pages() {
+        let arena = create().unwrap();
+
+        unsafe {
+            let ptr = jemalloc_sys::mallocx(4 * 1024 * 1024, arena.flags());
+            assert!(!ptr.is_null());
+            jemalloc_sys::dallocx(ptr, arena.flags());
+        }
+
+        purge(arena.index()).unwrap();
+        ::epoch().unwrap();
+        assert_eq!(stats::arenas::pdirty(arena.index()).unwrap(), 0);
+        assert_eq!(stats::arenas::pmuzzy(arena.index()).unwrap(), 0);
+
+        unsafe {
+            arena.destroy().unwrap();
+        }
+    }
+
+    #[test]
+    fn decay_time_round_trip() {
+        let arena = create().unwrap();
+
+        for &decay in &[
+            DecayTime::Immediate,
+            DecayTime::Disabled,
+            DecayTime::Millis(1234),
+        ] {
+            set_dirty_decay_ms(arena.index(), decay).unwrap();
+            assert_eq!(dirty_decay_ms(arena.index()).unwrap(), decay);
+            set_muzzy_decay_ms(arena.index(), decay).unwrap();
+            assert_eq!(muzzy_decay_ms(arena.index()).unwrap(), decay);
+        }
+
+        match set_dirty_decay_ms(arena.index(), DecayTime::Millis(!0)) {
+            Err(Error::Unsupported { .. }) => {}
+            r => panic!("unexpected result {:?}", r),
+        }
+
+        unsafe {
+            arena.destroy().unwrap();
+        }
+    }
+
+    #[test]
+    fn lookup_owner() {
+        let arena = create().unwrap();
+
+        unsafe {
+            let ptr = jemalloc_sys::mallocx(1024 * 1024, arena.flags()) as *const u8;
+            assert_eq!(lookup(ptr).unwrap(), arena);
+            jemalloc_sys::dallocx(ptr as *mut _, arena.flags());
+        }
+
+        // null is the only address tested without a registered extent, since whether jemalloc
+        // still has one registered for a freed allocation depends on its extent caching
+        match unsafe { lookup(ptr::null()) } {
+            Err(Error::InvalidValue { ref name }) => assert_eq!(name, "arenas.lookup"),
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn size_classes() {
+        let classes = SizeClasses::new().unwrap();
+        assert_eq!(classes.bins().len(), nbins().unwrap() as usize);
+        assert_eq!(classes.lextents().len(), nlextents().unwrap() as usize);
+
+        let smallest = classes.bins()[0].size;
+        assert_eq!(classes.class_for(0).unwrap().size(), smallest);
+        assert_eq!(classes.class_for(1).unwrap().size(), smallest);
+        assert_eq!(classes.waste_for(smallest), Some(0));
+
+        let largest_bin = *classes.bins().last().unwrap();
+        match classes.class_for(largest_bin.size + 1) {
+            Some(SizeClass::Large { lextent: 0, size }) => assert_eq!(size, classes.lextents()[0]),
+            r => panic!("unexpected class {:?}", r),
+        }
+
+        for (i, bin) in classes.bins().iter().enumerate() {
+            assert_eq!(
+                classes.class_for(bin.size),
+                Some(SizeClass::Small {
+                    bin: i as c_uint,
+                    size: bin.size,
+                })
+            );
+        }
+
+        unsafe {
+            for &size in &[1, 17, 4097, 100 * 1024, 3 * 1024 * 1024] {
+                let ptr = jemalloc_sys::mallocx(size, 0);
+                let usable = jemalloc_sys::sallocx(ptr, 0);
+                assert_eq!(classes.class_for(size).unwrap().size(), usable);
+                jemalloc_sys::dallocx(ptr, 0);
+            }
+        }
+
+        let largest = *classes.lextents().last().unwrap();
+        assert_eq!(classes.class_for(largest + 1), None);
     }
 }
@@ -0,0 +1,347 @@
+//! The configuration jemalloc is currently running with.
+use arenas::{self, DecayTime};
+use {available, background_thread, max_background_threads, opt, prof, thread, Result};
+
+use super::{MallocConfig, ParseError, Setting, Source};
+
+/// An option whose current value differs from the value it had at startup.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Difference {
+    /// The name of the option, e.g. `dirty_decay_ms`.
+    pub key: &'static str,
+    /// The name of the operation controlling the option at run time, e.g.
+    /// `arenas.dirty_decay_ms`.
+    pub control: &'static str,
+    /// The value of the option at startup, as it would appear in an option string.
+    pub startup: String,
+    /// The current value of the option, as it would appear in an option string.
+    pub current: String,
+}
+
+/// An option jemalloc is running with, along with where its value came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EffectiveOption {
+    /// The name of the option, e.g. `narenas`.
+    pub key: &'static str,
+    /// The value of the option at startup, as it would appear in an option string.
+    pub value: String,
+    /// The startup configuration which set the option, or `None` if jemalloc's default was used.
+    pub source: Option<Source>,
+}
+
+/// The configuration jemalloc is currently running with.
+///
+/// This is returned by [`effective`].
+///
+/// [`effective`]: fn.effective.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectiveConfig {
+    /// The options jemalloc was started with, as reported by the [`opt`] module.
+    ///
+    /// Each option is attributed to the startup configuration which set it, as reported by
+    /// [`MallocConfig::startup`]. Options which are not available in this build of jemalloc are
+    /// omitted.
+    ///
+    /// [`opt`]: ../opt/index.html
+    /// [`MallocConfig::startup`]: struct.MallocConfig.html#method.startup
+    pub options: Vec<EffectiveOption>,
+    /// The options in the startup configuration which jemalloc skipped because they are invalid.
+    pub startup_errors: Vec<ParseError>,
+    /// Whether background threads are currently enabled.
+    ///
+    /// This corresponds to `background_thread` in jemalloc's API.
+    pub background_thread: Option<bool>,
+    /// The current maximum number of background threads.
+    ///
+    /// This corresponds to `max_background_threads` in jemalloc's API.
+    pub max_background_threads: Option<usize>,
+    /// The current dirty page decay time for newly created arenas.
+    ///
+    /// This corresponds to `arenas.dirty_decay_ms` in jemalloc's API.
+    pub dirty_decay_ms: DecayTime,
+    /// The current muzzy page decay time for newly created arenas.
+    ///
+    /// This corresponds to `arenas.muzzy_decay_ms` in jemalloc's API.
+    pub muzzy_decay_ms: DecayTime,
+    /// Whether the calling thread's automatically managed thread cache is currently enabled.
+    ///
+    /// This corresponds to `thread.tcache.enabled` in jemalloc's API.
+    pub tcache: bool,
+    /// Whether sampling is currently active.
+    ///
+    /// This corresponds to `prof.active` in jemalloc's API.
+    pub prof_active: Option<bool>,
+    /// Whether sampling is currently initially active for newly created threads.
+    ///
+    /// This corresponds to `prof.thread_active_init` in jemalloc's API.
+    pub prof_thread_active_init: Option<bool>,
+    /// Whether a profile is currently dumped every time the total virtual memory exceeds its
+    /// previous maximum.
+    ///
+    /// This corresponds to `prof.gdump` in jemalloc's API.
+    pub prof_gdump: Option<bool>,
+    /// The current sample interval (log base 2).
+    ///
+    /// This corresponds to `prof.lg_sample` in jemalloc's API.
+    pub lg_prof_sample: Option<usize>,
+}
+
+impl EffectiveConfig {
+    /// Returns the option with the specified name, if it is available.
+    pub fn option(&self, key: &str) -> Option<&EffectiveOption> {
+        self.options.iter().find(|option| option.key == key)
+    }
+
+    /// Returns the options whose current value differs from the value they had at startup.
+    ///
+    /// The following options are compared with the operations controlling them at run time:
+    ///
+    /// | Option                    | Control                   |
+    /// |---------------------------|---------------------------|
+    /// | `background_thread`       | `background_thread`       |
+    /// | `max_background_threads`  | `max_background_threads`  |
+    /// | `dirty_decay_ms`          | `arenas.dirty_decay_ms`   |
+    /// | `muzzy_decay_ms`          | `arenas.muzzy_decay_ms`   |
+    /// | `tcache`                  | `thread.tcache.enabled`   |
+    /// | `prof_active`             | `prof.active`             |
+    /// | `prof_thread_active_init` | `prof.thread_active_init` |
+    /// | `prof_gdump`              | `prof.gdump`              |
+    /// | `lg_prof_sample`          | `prof.lg_sample`          |
+    ///
+    /// `tcache` is compared with the setting of the thread which called [`effective`]. Other
+    /// options can't be changed at run time, except for per-arena decay times and per-thread
+    /// sampling, which are not compared.
+    ///
+    /// [`effective`]: fn.effective.html
+    pub fn differences(&self) -> Vec<Difference> {
+        let mut differences = vec![];
+        self.difference(
+            &mut differences,
+            "background_thread",
+            "background_thread",
+            self.background_thread.map(|v| v.to_string()),
+        );
+        self.difference(
+            &mut differences,
+            "max_background_threads",
+            "max_background_threads",
+            self.max_background_threads.map(|v| v.to_string()),
+        );
+        self.difference(
+            &mut differences,
+            "dirty_decay_ms",
+            "arenas.dirty_decay_ms",
+            Some(self.dirty_decay_ms.to_raw().to_string()),
+        );
+        self.difference(
+            &mut differences,
+            "muzzy_decay_ms",
+            "arenas.muzzy_decay_ms",
+            Some(self.muzzy_decay_ms.to_raw().to_string()),
+        );
+        self.difference(
+            &mut differences,
+            "tcache",
+            "thread.tcache.enabled",
+            Some(self.tcache.to_string()),
+        );
+        self.difference(
+            &mut differences,
+            "prof_active",
+            "prof.active",
+            self.prof_active.map(|v| v.to_string()),
+        );
+        self.difference(
+            &mut differences,
+            "prof_thread_active_init",
+            "prof.thread_active_init",
+            self.prof_thread_active_init.map(|v| v.to_string()),
+        );
+        self.difference(
+            &mut differences,
+            "prof_gdump",
+            "prof.gdump",
+            self.prof_gdump.map(|v| v.to_string()),
+        );
+        self.difference(
+            &mut differences,
+            "lg_prof_sample",
+            "prof.lg_sample",
+            self.lg_prof_sample.map(|v| v.to_string()),
+        );
+        differences
+    }
+
+    fn difference(
+        &self,
+        differences: &mut Vec<Difference>,
+        key: &'static str,
+        control: &'static str,
+        current: Option<String>,
+    ) {
+        if let (Some(startup), Some(current)) = (self.option(key), current) {
+            if startup.value != current {
+                differences.push(Difference {
+                    key,
+                    control,
+                    startup: startup.value.clone(),
+                    current,
+                });
+            }
+        }
+    }
+}
+
+/// Returns the configuration jemalloc is currently running with.
+///
+/// This reads every option in the [`opt`] module, along with the current values of the options
+/// which can be changed at run time. Options are attributed to the startup configuration as
+/// jemalloc parsed it, so invalid options it skipped are reported in
+/// [`EffectiveConfig::startup_errors`] rather than affecting attribution.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas::{self, DecayTime};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     arenas::set_default_dirty_decay_ms(DecayTime::Millis(1234)).unwrap();
+///
+///     let config = jemalloc_ctl::config::effective().unwrap();
+///     for option in &config.options {
+///         match option.source {
+///             Some(source) => println!("{}:{} ({})", option.key, option.value, source),
+///             None => println!("{}:{} (default)", option.key, option.value),
+///         }
+///     }
+///
+///     let differences = config.differences();
+///     assert!(differences.iter().any(|d| d.key == "dirty_decay_ms" && d.current == "1234"));
+///     for d in differences {
+///         println!("{} changed from {} to {} via {}", d.key, d.startup, d.current, d.control);
+///     }
+/// }
+/// ```
+///
+/// [`opt`]: ../opt/index.html
+/// [`EffectiveConfig::startup_errors`]: struct.EffectiveConfig.html#structfield.startup_errors
+pub fn effective() -> Result<EffectiveConfig> {
+    // jemalloc skips invalid options, so the startup configuration is parsed the same way
+    let (startup, startup_errors) = MallocConfig::startup()?;
+    let startup = startup.entries();
+    let values = MallocConfig {
+        abort: available(opt::abort())?.map(Setting::new),
+        abort_conf: available(opt::abort_conf())?.map(Setting::new),
+        metadata_thp: available(opt::metadata_thp())?.map(Setting::new),
+        retain: available(opt::retain())?.map(Setting::new),
+        dss: available(opt::dss())?.map(Setting::new),
+        narenas: available(opt::narenas())?.map(Setting::new),
+        percpu_arena: available(opt::percpu_arena())?.map(Setting::new),
+        oversize_threshold: available(opt::oversize_threshold())?.map(Setting::new),
+        background_thread: available(opt::background_thread())?.map(Setting::new),
+        max_background_threads: available(opt::max_background_threads())?.map(Setting::new),
+        dirty_decay_ms: available(opt::dirty_decay_ms())?.map(Setting::new),
+        muzzy_decay_ms: available(opt::muzzy_decay_ms())?.map(Setting::new),
+        junk: available(opt::junk())?.map(Setting::new),
+        zero: available(opt::zero())?.map(Setting::new),
+        utrace: available(opt::utrace())?.map(Setting::new),
+        xmalloc: available(opt::xmalloc())?.map(Setting::new),
+        tcache: available(opt::tcache())?.map(Setting::new),
+        lg_tcache_max: available(opt::lg_tcache_max())?.map(Setting::new),
+        thp: available(opt::thp())?.map(Setting::new),
+        lg_extent_max_active_fit: available(opt::lg_extent_max_active_fit())?.map(Setting::new),
+        prof: available(opt::prof())?.map(Setting::new),
+        prof_prefix: available(opt::prof_prefix())?
+            .map(str::to_string)
+            .map(Setting::new),
+        prof_active: available(opt::prof_active())?.map(Setting::new),
+        prof_thread_active_init: available(opt::prof_thread_active_init())?.map(Setting::new),
+        lg_prof_sample: available(opt::lg_prof_sample())?.map(Setting::new),
+        lg_prof_interval: available(opt::lg_prof_interval())?.map(Setting::new),
+        prof_gdump: available(opt::prof_gdump())?.map(Setting::new),
+        prof_final: available(opt::prof_final())?.map(Setting::new),
+        prof_leak: available(opt::prof_leak())?.map(Setting::new),
+        prof_accum: available(opt::prof_accum())?.map(Setting::new),
+        stats_print: available(opt::stats_print())?.map(Setting::new),
+        stats_print_opts: available(opt::stats_print_opts())?
+            .map(str::to_string)
+            .map(Setting::new),
+    };
+    let options = values
+        .entries()
+        .into_iter()
+        .map(|entry| EffectiveOption {
+            key: entry.key,
+            source: startup
+                .iter()
+                .find(|startup| startup.key == entry.key)
+                .map(|startup| startup.source),
+            value: entry.value,
+        })
+        .collect();
+
+    Ok(EffectiveConfig {
+        options,
+        startup_errors,
+        background_thread: available(background_thread())?,
+        max_background_threads: available(max_background_threads())?,
+        dirty_decay_ms: arenas::default_dirty_decay_ms()?,
+        muzzy_decay_ms: arenas::default_muzzy_decay_ms()?,
+        tcache: thread::tcache_enabled()?,
+        prof_active: available(prof::active())?,
+        prof_thread_active_init: available(prof::thread_active_init())?,
+        prof_gdump: available(prof::gdump())?,
+        lg_prof_sample: available(prof::lg_sample())?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reports_changes() {
+        let config = effective().unwrap();
+        assert_eq!(
+            config.option("narenas").unwrap().value,
+            opt::narenas().unwrap().to_string()
+        );
+        assert_eq!(
+            config.option("dss").unwrap().value,
+            opt::dss().unwrap().as_str()
+        );
+        if !::config::prof().unwrap() {
+            assert_eq!(config.option("prof"), None);
+            assert_eq!(config.prof_active, None);
+        }
+
+        assert_eq!(config.differences(), vec![]);
+
+        let muzzy_decay_ms = arenas::default_muzzy_decay_ms().unwrap();
+        let tcache = thread::tcache_enabled().unwrap();
+        arenas::set_default_muzzy_decay_ms(DecayTime::Millis(1234)).unwrap();
+        thread::set_tcache_enabled(!tcache).unwrap();
+        let differences = effective().unwrap().differences();
+        arenas::set_default_muzzy_decay_ms(muzzy_decay_ms).unwrap();
+        thread::set_tcache_enabled(tcache).unwrap();
+
+        assert!(differences.contains(&Difference {
+            key: "muzzy_decay_ms",
+            control: "arenas.muzzy_decay_ms",
+            startup: opt::muzzy_decay_ms().unwrap().to_raw().to_string(),
+            current: "1234".to_string(),
+        }));
+        assert!(differences.contains(&Difference {
+            key: "tcache",
+            control: "thread.tcache.enabled",
+            startup: tcache.to_string(),
+            current: (!tcache).to_string(),
+        }));
+    }
+}
@@ -0,0 +1,658 @@
+//! Parsing and rendering of `malloc_conf` option strings.
+use jemalloc_sys;
+use libc::ssize_t;
+use std::env;
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::fs;
+use std::os::raw::{c_char, c_uint};
+use std::result;
+use std::str::FromStr;
+
+use arenas::DecayTime;
+use opt::{DssMode, JunkMode, MetadataThpMode, PercpuArenaMode, ThpMode};
+
+use super::malloc_conf;
+use Result;
+
+// The names jemalloc reads options from depend on whether it was built with a symbol prefix,
+// which is detected by the build script.
+#[cfg(prefixed)]
+const LINK: &str = "/etc/_rjem_malloc.conf";
+#[cfg(not(prefixed))]
+const LINK: &str = "/etc/malloc.conf";
+#[cfg(prefixed)]
+const ENV_VAR: &str = "_RJEM_MALLOC_CONF";
+#[cfg(not(prefixed))]
+const ENV_VAR: &str = "MALLOC_CONF";
+
+/// Where an option's value came from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// The configuration embedded at build time with `--with-malloc-conf`.
+    ///
+    /// This is the value of [`config::malloc_conf`].
+    ///
+    /// [`config::malloc_conf`]: fn.malloc_conf.html
+    Builtin,
+
+    /// The program's `malloc_conf` global variable.
+    Symbol,
+
+    /// The name of the symbolic link at the specified path.
+    Link(&'static str),
+
+    /// The environment variable with the specified name.
+    Environment(&'static str),
+
+    /// A string parsed with [`MallocConfig::parse`] or `str::parse`, or a value set directly.
+    ///
+    /// [`MallocConfig::parse`]: struct.MallocConfig.html#method.parse
+    Explicit,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Source::Builtin => fmt.write_str("built-in configuration"),
+            Source::Symbol => fmt.write_str("`malloc_conf` global variable"),
+            Source::Link(path) => write!(fmt, "symbolic link `{}`", path),
+            Source::Environment(name) => write!(fmt, "environment variable `{}`", name),
+            Source::Explicit => fmt.write_str("explicit configuration"),
+        }
+    }
+}
+
+/// The value of an option, along with where it came from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Setting<T> {
+    /// The value of the option.
+    pub value: T,
+    /// Where the value came from.
+    pub source: Source,
+}
+
+impl<T> Setting<T> {
+    /// Returns a new `Setting` with an [`Explicit`] source.
+    ///
+    /// [`Explicit`]: enum.Source.html#variant.Explicit
+    pub fn new(value: T) -> Setting<T> {
+        Setting {
+            value,
+            source: Source::Explicit,
+        }
+    }
+}
+
+impl<T> From<T> for Setting<T> {
+    fn from(value: T) -> Setting<T> {
+        Setting::new(value)
+    }
+}
+
+/// A rendered option of a [`MallocConfig`].
+///
+/// [`MallocConfig`]: struct.MallocConfig.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Entry {
+    /// The name of the option, e.g. `narenas`.
+    pub key: &'static str,
+    /// The value of the option, as it would appear in an option string.
+    pub value: String,
+    /// Where the value came from.
+    pub source: Source,
+}
+
+// A value which can appear in an option string.
+trait Value: Sized {
+    fn parse(s: &str) -> Option<Self>;
+
+    fn render(&self) -> String;
+}
+
+impl Value for bool {
+    fn parse(s: &str) -> Option<bool> {
+        match s {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        }
+    }
+
+    fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+macro_rules! numeric_values {
+    ($($t:ty)*) => {
+        $(
+            impl Value for $t {
+                fn parse(s: &str) -> Option<$t> {
+                    s.parse().ok()
+                }
+
+                fn render(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    }
+}
+
+numeric_values!(c_uint usize ssize_t);
+
+impl Value for String {
+    fn parse(s: &str) -> Option<String> {
+        Some(s.to_string())
+    }
+
+    fn render(&self) -> String {
+        self.clone()
+    }
+}
+
+impl Value for DecayTime {
+    fn parse(s: &str) -> Option<DecayTime> {
+        match s.parse() {
+            Ok(ms) if ms >= -1 => Some(DecayTime::from_raw(ms)),
+            _ => None,
+        }
+    }
+
+    fn render(&self) -> String {
+        self.to_raw().to_string()
+    }
+}
+
+macro_rules! mode_values {
+    ($($t:ty)*) => {
+        $(
+            impl Value for $t {
+                fn parse(s: &str) -> Option<$t> {
+                    s.parse().ok()
+                }
+
+                fn render(&self) -> String {
+                    self.as_str().to_string()
+                }
+            }
+        )*
+    }
+}
+
+mode_values!(DssMode JunkMode MetadataThpMode PercpuArenaMode ThpMode);
+
+macro_rules! malloc_config {
+    ($($(#[$attr:meta])* $key:ident: $t:ty,)*) => {
+        /// A set of jemalloc options, as written in a `malloc_conf` string.
+        ///
+        /// Option strings consist of comma-separated `key:value` pairs, such as
+        /// `narenas:4,dirty_decay_ms:5000`. `MallocConfig` parses them into typed fields matching
+        /// the [`opt`] module, tracks where each value came from, and renders back to a string with
+        /// its `Display` implementation. Options which are not set are `None`, and take jemalloc's
+        /// defaults.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// extern crate jemallocator;
+        /// extern crate jemalloc_ctl;
+        ///
+        /// use jemalloc_ctl::arenas::DecayTime;
+        /// use jemalloc_ctl::config::{MallocConfig, Source};
+        /// use jemalloc_ctl::opt::ThpMode;
+        ///
+        /// #[global_allocator]
+        /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+        ///
+        /// fn main() {
+        ///     let mut config = "narenas:4,dirty_decay_ms:-1".parse::<MallocConfig>().unwrap();
+        ///     assert_eq!(config.narenas.unwrap().value, 4);
+        ///     assert_eq!(config.dirty_decay_ms.unwrap().value, DecayTime::Disabled);
+        ///
+        ///     config.thp = Some(ThpMode::Never.into());
+        ///     assert_eq!(config.to_string(), "narenas:4,dirty_decay_ms:-1,thp:never");
+        ///
+        ///     let env = MallocConfig::parse("narenas:8", Source::Environment("MALLOC_CONF"));
+        ///     config.merge(env.unwrap());
+        ///     for entry in config.entries() {
+        ///         println!("{}:{} ({})", entry.key, entry.value, entry.source);
+        ///     }
+        /// }
+        /// ```
+        ///
+        /// [`opt`]: ../opt/index.html
+        #[derive(Debug, Clone, Default, PartialEq)]
+        pub struct MallocConfig {
+            $(
+                $(#[$attr])*
+                pub $key: Option<Setting<$t>>,
+            )*
+        }
+
+        impl MallocConfig {
+            // Sets the named option from its string representation.
+            fn set(
+                &mut self,
+                key: &str,
+                value: &str,
+                source: Source,
+            ) -> result::Result<(), ErrorKind> {
+                match key {
+                    $(
+                        stringify!($key) => {
+                            let value = Value::parse(value).ok_or(ErrorKind::InvalidValue)?;
+                            self.$key = Some(Setting { value, source });
+                        }
+                    )*
+                    _ => return Err(ErrorKind::UnknownKey),
+                }
+                Ok(())
+            }
+
+            /// Overrides the options in this configuration with those set in `other`.
+            ///
+            /// Options which are not set in `other` are left unchanged.
+            pub fn merge(&mut self, other: MallocConfig) {
+                $(
+                    if other.$key.is_some() {
+                        self.$key = other.$key;
+                    }
+                )*
+            }
+
+            /// Returns the options which are set, in the order they are rendered.
+            pub fn entries(&self) -> Vec<Entry> {
+                let mut entries = vec![];
+                $(
+                    if let Some(ref setting) = self.$key {
+                        entries.push(Entry {
+                            key: stringify!($key),
+                            value: setting.value.render(),
+                            source: setting.source,
+                        });
+                    }
+                )*
+                entries
+            }
+        }
+    }
+}
+
+malloc_config! {
+    /// See [`opt::abort`](../opt/fn.abort.html).
+    abort: bool,
+    /// See [`opt::abort_conf`](../opt/fn.abort_conf.html).
+    abort_conf: bool,
+    /// See [`opt::metadata_thp`](../opt/fn.metadata_thp.html).
+    metadata_thp: MetadataThpMode,
+    /// See [`opt::retain`](../opt/fn.retain.html).
+    retain: bool,
+    /// See [`opt::dss`](../opt/fn.dss.html).
+    dss: DssMode,
+    /// See [`opt::narenas`](../opt/fn.narenas.html).
+    narenas: c_uint,
+    /// See [`opt::percpu_arena`](../opt/fn.percpu_arena.html).
+    percpu_arena: PercpuArenaMode,
+    /// See [`opt::oversize_threshold`](../opt/fn.oversize_threshold.html).
+    oversize_threshold: usize,
+    /// See [`opt::background_thread`](../opt/fn.background_thread.html).
+    background_thread: bool,
+    /// See [`opt::max_background_threads`](../opt/fn.max_background_threads.html).
+    max_background_threads: usize,
+    /// See [`opt::dirty_decay_ms`](../opt/fn.dirty_decay_ms.html).
+    dirty_decay_ms: DecayTime,
+    /// See [`opt::muzzy_decay_ms`](../opt/fn.muzzy_decay_ms.html).
+    muzzy_decay_ms: DecayTime,
+    /// See [`opt::junk`](../opt/fn.junk.html).
+    junk: JunkMode,
+    /// See [`opt::zero`](../opt/fn.zero.html).
+    zero: bool,
+    /// See [`opt::utrace`](../opt/fn.utrace.html).
+    utrace: bool,
+    /// See [`opt::xmalloc`](../opt/fn.xmalloc.html).
+    xmalloc: bool,
+    /// See [`opt::tcache`](../opt/fn.tcache.html).
+    tcache: bool,
+    /// See [`opt::lg_tcache_max`](../opt/fn.lg_tcache_max.html).
+    lg_tcache_max: ssize_t,
+    /// See [`opt::thp`](../opt/fn.thp.html).
+    thp: ThpMode,
+    /// See [`opt::lg_extent_max_active_fit`](../opt/fn.lg_extent_max_active_fit.html).
+    lg_extent_max_active_fit: usize,
+    /// See [`opt::prof`](../opt/fn.prof.html).
+    prof: bool,
+    /// See [`opt::prof_prefix`](../opt/fn.prof_prefix.html).
+    prof_prefix: String,
+    /// See [`opt::prof_active`](../opt/fn.prof_active.html).
+    prof_active: bool,
+    /// See [`opt::prof_thread_active_init`](../opt/fn.prof_thread_active_init.html).
+    prof_thread_active_init: bool,
+    /// See [`opt::lg_prof_sample`](../opt/fn.lg_prof_sample.html).
+    lg_prof_sample: usize,
+    /// See [`opt::lg_prof_interval`](../opt/fn.lg_prof_interval.html).
+    lg_prof_interval: ssize_t,
+    /// See [`opt::prof_gdump`](../opt/fn.prof_gdump.html).
+    prof_gdump: bool,
+    /// See [`opt::prof_final`](../opt/fn.prof_final.html).
+    prof_final: bool,
+    /// See [`opt::prof_leak`](../opt/fn.prof_leak.html).
+    prof_leak: bool,
+    /// See [`opt::prof_accum`](../opt/fn.prof_accum.html).
+    prof_accum: bool,
+    /// See [`opt::stats_print`](../opt/fn.stats_print.html).
+    stats_print: bool,
+    /// See [`opt::stats_print_opts`](../opt/fn.stats_print_opts.html).
+    stats_print_opts: String,
+}
+
+impl MallocConfig {
+    /// Returns a new `MallocConfig` with no options set.
+    pub fn new() -> MallocConfig {
+        MallocConfig::default()
+    }
+
+    /// Parses an option string, attributing its values to `source`.
+    ///
+    /// This is stricter than jemalloc, which warns about and skips invalid options when it reads
+    /// its configuration: an error is returned for the first malformed pair, unknown key or
+    /// invalid value. See [`parse_lenient`] for a version which skips them instead.
+    ///
+    /// [`parse_lenient`]: #method.parse_lenient
+    pub fn parse(conf: &str, source: Source) -> result::Result<MallocConfig, ParseError> {
+        let (config, errors) = MallocConfig::parse_lenient(conf, source);
+        match errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(config),
+        }
+    }
+
+    /// Parses an option string, attributing its values to `source` and skipping invalid options.
+    ///
+    /// As with jemalloc, malformed pairs, unknown keys and invalid values are skipped, and the
+    /// remaining options are applied. An error is returned for each option which was skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate jemallocator;
+    /// extern crate jemalloc_ctl;
+    ///
+    /// use jemalloc_ctl::config::{MallocConfig, Source};
+    ///
+    /// #[global_allocator]
+    /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+    ///
+    /// fn main() {
+    ///     let (config, errors) = MallocConfig::parse_lenient("narenas:4,bogus:1", Source::Explicit);
+    ///     assert_eq!(config.to_string(), "narenas:4");
+    ///     assert_eq!(errors.len(), 1);
+    /// }
+    /// ```
+    pub fn parse_lenient(conf: &str, source: Source) -> (MallocConfig, Vec<ParseError>) {
+        let mut config = MallocConfig::new();
+        let mut errors = vec![];
+        if conf.is_empty() {
+            return (config, errors);
+        }
+
+        for pair in conf.split(',') {
+            if let Err(kind) = config.set_pair(pair, source) {
+                errors.push(ParseError {
+                    kind,
+                    pair: pair.to_string(),
+                    source,
+                });
+            }
+        }
+
+        (config, errors)
+    }
+
+    // Sets the option in a `key:value` pair.
+    fn set_pair(&mut self, pair: &str, source: Source) -> result::Result<(), ErrorKind> {
+        let mut parts = pair.splitn(2, ':');
+        let key = parts.next().unwrap_or("");
+        let value = parts.next().ok_or(ErrorKind::Syntax)?;
+        let valid_key = key.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_');
+        if key.is_empty() || !valid_key {
+            return Err(ErrorKind::Syntax);
+        }
+        self.set(key, value, source)
+    }
+
+    /// Returns the configuration jemalloc read at startup.
+    ///
+    /// As with jemalloc, the configuration is read from the following sources, with options set in
+    /// later sources overriding earlier ones:
+    ///
+    /// 1. The built-in configuration, as reported by [`config::malloc_conf`].
+    /// 2. The program's `malloc_conf` global variable.
+    /// 3. The name of the `/etc/malloc.conf` symbolic link, or `/etc/_rjem_malloc.conf` if
+    ///    jemalloc's symbols are prefixed. This is not read on Windows.
+    /// 4. The `MALLOC_CONF` environment variable, or `_RJEM_MALLOC_CONF` if jemalloc's symbols are
+    ///    prefixed.
+    ///
+    /// Invalid options are skipped, as jemalloc does, and an error is returned alongside the
+    /// configuration for each of them.
+    ///
+    /// The symbolic link and environment variable are read when this is called, rather than when
+    /// jemalloc started. If they have been changed since, for example with `env::set_var`, the
+    /// result will not match the options jemalloc applied. The environment variable is also read
+    /// even if jemalloc ignored it because the program is running setuid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate jemallocator;
+    /// extern crate jemalloc_ctl;
+    ///
+    /// use jemalloc_ctl::config::MallocConfig;
+    ///
+    /// #[global_allocator]
+    /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+    ///
+    /// fn main() {
+    ///     let (config, errors) = MallocConfig::startup().unwrap();
+    ///     println!("malloc conf: {}", config);
+    ///     for error in errors {
+    ///         println!("skipped: {}", error);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`config::malloc_conf`]: fn.malloc_conf.html
+    pub fn startup() -> Result<(MallocConfig, Vec<ParseError>)> {
+        let mut config = MallocConfig::new();
+        let mut errors = vec![];
+        let mut read = |conf: &str, source| {
+            let (other, other_errors) = MallocConfig::parse_lenient(conf, source);
+            config.merge(other);
+            errors.extend(other_errors);
+        };
+
+        read(malloc_conf()?, Source::Builtin);
+        if let Some(conf) = symbol_conf() {
+            read(&conf, Source::Symbol);
+        }
+        if let Some(conf) = link_conf() {
+            read(&conf, Source::Link(LINK));
+        }
+        if let Some(conf) = env::var_os(ENV_VAR) {
+            read(&conf.to_string_lossy(), Source::Environment(ENV_VAR));
+        }
+
+        Ok((config, errors))
+    }
+
+    /// Determines if no options are set.
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+}
+
+// Returns the value of the program's `malloc_conf` global variable, which jemalloc defines as a
+// null weak symbol.
+fn symbol_conf() -> Option<String> {
+    unsafe {
+        jemalloc_sys::malloc_conf.map(|conf| {
+            CStr::from_ptr(conf as *const c_char)
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+}
+
+// Returns the name of the symbolic link jemalloc reads options from, which it ignores if it can't
+// be read.
+fn link_conf() -> Option<String> {
+    if cfg!(windows) {
+        return None;
+    }
+    fs::read_link(LINK)
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+impl FromStr for MallocConfig {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> result::Result<MallocConfig, ParseError> {
+        MallocConfig::parse(s, Source::Explicit)
+    }
+}
+
+impl fmt::Display for MallocConfig {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for (i, entry) in self.entries().iter().enumerate() {
+            if i > 0 {
+                fmt.write_str(",")?;
+            }
+            write!(fmt, "{}:{}", entry.key, entry.value)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ErrorKind {
+    Syntax,
+    UnknownKey,
+    InvalidValue,
+}
+
+/// An error returned when parsing an option string fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ErrorKind,
+    pair: String,
+    source: Source,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Syntax => write!(fmt, "malformed option `{}`", self.pair)?,
+            ErrorKind::UnknownKey => write!(fmt, "unknown option `{}`", self.pair)?,
+            ErrorKind::InvalidValue => write!(fmt, "invalid value in option `{}`", self.pair)?,
+        }
+        write!(fmt, " in {}", self.source)
+    }
+}
+
+impl error::Error for ParseError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_render() {
+        let conf = concat!(
+            "abort:true,dss:primary,narenas:4,muzzy_decay_ms:0,junk:alloc,lg_tcache_max:-1,",
+            "stats_print_opts:gblam"
+        );
+        let config = conf.parse::<MallocConfig>().unwrap();
+        assert_eq!(config.abort, Some(Setting::new(true)));
+        assert_eq!(config.dss.unwrap().value, DssMode::Primary);
+        assert_eq!(config.muzzy_decay_ms.unwrap().value, DecayTime::Immediate);
+        assert_eq!(config.junk.unwrap().value, JunkMode::Alloc);
+        assert_eq!(config.lg_tcache_max.unwrap().value, -1);
+        assert_eq!(config.to_string(), conf);
+
+        assert!("".parse::<MallocConfig>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_errors() {
+        for &conf in &[
+            "narenas",
+            "narenas:4,",
+            ":4",
+            "nar-enas:4",
+            "bogus:1",
+            "narenas:four",
+            "abort:yes",
+            "dirty_decay_ms:-2",
+        ] {
+            assert!(conf.parse::<MallocConfig>().is_err(), "{}", conf);
+        }
+
+        let error = MallocConfig::parse("tcache:maybe", Source::Environment("MALLOC_CONF"));
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "invalid value in option `tcache:maybe` in environment variable `MALLOC_CONF`"
+        );
+    }
+
+    #[test]
+    fn parse_lenient_skips_invalid() {
+        let (config, errors) = MallocConfig::parse_lenient(
+            "narenas:4,bogus:1,,dirty_decay_ms:-1,abort:yes",
+            Source::Builtin,
+        );
+        assert_eq!(config.to_string(), "narenas:4,dirty_decay_ms:-1");
+        assert_eq!(
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            vec![
+                "unknown option `bogus:1` in built-in configuration",
+                "malformed option `` in built-in configuration",
+                "invalid value in option `abort:yes` in built-in configuration",
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_precedence() {
+        let mut config = MallocConfig::parse("narenas:2,tcache:false", Source::Builtin).unwrap();
+        let env = MallocConfig::parse("narenas:8", Source::Environment("MALLOC_CONF")).unwrap();
+        config.merge(env);
+
+        assert_eq!(
+            config.entries(),
+            vec![
+                Entry {
+                    key: "narenas",
+                    value: "8".to_string(),
+                    source: Source::Environment("MALLOC_CONF"),
+                },
+                Entry {
+                    key: "tcache",
+                    value: "false".to_string(),
+                    source: Source::Builtin,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn startup_matches_options() {
+        let (config, errors) = MallocConfig::startup().unwrap();
+        assert_eq!(errors, vec![]);
+        if let Some(narenas) = config.narenas {
+            assert_eq!(narenas.value, ::opt::narenas().unwrap());
+        }
+    }
+}
@@ -0,0 +1,685 @@
+//! Information about the jemalloc compile-time configuration
+//!
+//! Features which are not compiled in report a [`Error::NotConfigured`] error when their
+//! operations are used. [`capabilities`] can be used to check for them up front instead.
+//!
+//! The run-time options jemalloc was started with can be inspected with [`MallocConfig`], which
+//! parses the `malloc_conf` option string syntax, and [`effective`] reports how they have been
+//! changed since.
+//!
+//! [`Error::NotConfigured`]: ../enum.Error.html#variant.NotConfigured
+//! [`capabilities`]: fn.capabilities.html
+//! [`MallocConfig`]: struct.MallocConfig.html
+//! [`effective`]: fn.effective.html
+
+use {get, get_str, Mib, MibStr, Result};
+
+pub use self::effective::{effective, Difference, EffectiveConfig, EffectiveOption};
+pub use self::malloc_config::{Entry, MallocConfig, ParseError, Setting, Source};
+
+mod effective;
+mod malloc_config;
+
+const MALLOC_CONF: &[u8] = b"config.malloc_conf\0";
+
+/// Returns the embeddec configure-time-specified run-time options config.
+///
+/// The string will be empty unless `--with-malloc-conf` was specified during build configuration.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("default malloc conf: {}", jemalloc_ctl::config::malloc_conf().unwrap());
+/// }
+/// ```
+pub fn malloc_conf() -> Result<&'static str> {
+    unsafe { get_str(MALLOC_CONF) }
+}
+
+/// A type providing access to the embedded configure-time-specified run-time options config.
+///
+/// The string will be empty unless `--with-malloc-conf` was specified during build configuration.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::MallocConf;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let malloc_conf = MallocConf::new().unwrap();
+///
+///     println!("default malloc conf: {}", malloc_conf.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct MallocConf(MibStr<[usize; 2]>);
+
+impl MallocConf {
+    /// Returns a new `MallocConf`.
+    pub fn new() -> Result<MallocConf> {
+        unsafe { MibStr::new(MALLOC_CONF).map(MallocConf) }
+    }
+
+    /// Returns the embedded configure-time-specified run-time options config.
+    pub fn get(&self) -> Result<&'static str> {
+        self.0.read()
+    }
+}
+
+const DEBUG: &[u8] = b"config.debug\0";
+
+/// Determines if `--enable-debug` was specified during build configuration.
+///
+/// Debug builds enable runtime assertions, and default to junk filling and aborting on warnings.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("debug: {}", jemalloc_ctl::config::debug().unwrap());
+/// }
+/// ```
+pub fn debug() -> Result<bool> {
+    unsafe { get(DEBUG) }
+}
+
+/// A type determining if `--enable-debug` was specified during build configuration.
+///
+/// Debug builds enable runtime assertions, and default to junk filling and aborting on warnings.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::Debug;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let debug = Debug::new().unwrap();
+///
+///     println!("debug: {}", debug.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Debug(Mib<bool, [usize; 2]>);
+
+impl Debug {
+    /// Returns a new `Debug`.
+    pub fn new() -> Result<Debug> {
+        unsafe { Mib::new(DEBUG).map(Debug) }
+    }
+
+    /// Returns the debug build configuration.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const FILL: &[u8] = b"config.fill\0";
+
+/// Determines if `--enable-fill` was specified during build configuration.
+///
+/// This enables support for the `junk` and `zero` options.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("fill: {}", jemalloc_ctl::config::fill().unwrap());
+/// }
+/// ```
+pub fn fill() -> Result<bool> {
+    unsafe { get(FILL) }
+}
+
+/// A type determining if `--enable-fill` was specified during build configuration.
+///
+/// This enables support for the `junk` and `zero` options.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::Fill;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let fill = Fill::new().unwrap();
+///
+///     println!("fill: {}", fill.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Fill(Mib<bool, [usize; 2]>);
+
+impl Fill {
+    /// Returns a new `Fill`.
+    pub fn new() -> Result<Fill> {
+        unsafe { Mib::new(FILL).map(Fill) }
+    }
+
+    /// Returns the fill build configuration.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const LAZY_LOCK: &[u8] = b"config.lazy_lock\0";
+
+/// Determines if `--enable-lazy-lock` was specified during build configuration.
+///
+/// With lazy locking, locking is avoided until the process first creates a thread.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("lazy lock: {}", jemalloc_ctl::config::lazy_lock().unwrap());
+/// }
+/// ```
+pub fn lazy_lock() -> Result<bool> {
+    unsafe { get(LAZY_LOCK) }
+}
+
+/// A type determining if `--enable-lazy-lock` was specified during build configuration.
+///
+/// With lazy locking, locking is avoided until the process first creates a thread.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::LazyLock;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let lazy_lock = LazyLock::new().unwrap();
+///
+///     println!("lazy lock: {}", lazy_lock.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct LazyLock(Mib<bool, [usize; 2]>);
+
+impl LazyLock {
+    /// Returns a new `LazyLock`.
+    pub fn new() -> Result<LazyLock> {
+        unsafe { Mib::new(LAZY_LOCK).map(LazyLock) }
+    }
+
+    /// Returns the lazy lock build configuration.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const PROF: &[u8] = b"config.prof\0";
+
+/// Determines if `--enable-prof` was specified during build configuration.
+///
+/// This enables support for heap profiling, including the operations in the `prof` module.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("prof: {}", jemalloc_ctl::config::prof().unwrap());
+/// }
+/// ```
+pub fn prof() -> Result<bool> {
+    unsafe { get(PROF) }
+}
+
+/// A type determining if `--enable-prof` was specified during build configuration.
+///
+/// This enables support for heap profiling, including the operations in the `prof` module.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::Prof;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof = Prof::new().unwrap();
+///
+///     println!("prof: {}", prof.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Prof(Mib<bool, [usize; 2]>);
+
+impl Prof {
+    /// Returns a new `Prof`.
+    pub fn new() -> Result<Prof> {
+        unsafe { Mib::new(PROF).map(Prof) }
+    }
+
+    /// Returns the prof build configuration.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const PROF_LIBGCC: &[u8] = b"config.prof_libgcc\0";
+
+/// Determines if `--disable-prof-libgcc` was not specified during build configuration.
+///
+/// libgcc is used to unwind the stack when taking heap profiling backtraces if it is available.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("prof libgcc: {}", jemalloc_ctl::config::prof_libgcc().unwrap());
+/// }
+/// ```
+pub fn prof_libgcc() -> Result<bool> {
+    unsafe { get(PROF_LIBGCC) }
+}
+
+/// A type determining if `--disable-prof-libgcc` was not specified during build configuration.
+///
+/// libgcc is used to unwind the stack when taking heap profiling backtraces if it is available.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::ProfLibgcc;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_libgcc = ProfLibgcc::new().unwrap();
+///
+///     println!("prof libgcc: {}", prof_libgcc.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfLibgcc(Mib<bool, [usize; 2]>);
+
+impl ProfLibgcc {
+    /// Returns a new `ProfLibgcc`.
+    pub fn new() -> Result<ProfLibgcc> {
+        unsafe { Mib::new(PROF_LIBGCC).map(ProfLibgcc) }
+    }
+
+    /// Returns the prof libgcc build configuration.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const PROF_LIBUNWIND: &[u8] = b"config.prof_libunwind\0";
+
+/// Determines if `--enable-prof-libunwind` was specified during build configuration.
+///
+/// libunwind is then used to unwind the stack when taking heap profiling backtraces.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("prof libunwind: {}", jemalloc_ctl::config::prof_libunwind().unwrap());
+/// }
+/// ```
+pub fn prof_libunwind() -> Result<bool> {
+    unsafe { get(PROF_LIBUNWIND) }
+}
+
+/// A type determining if `--enable-prof-libunwind` was specified during build configuration.
+///
+/// libunwind is then used to unwind the stack when taking heap profiling backtraces.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::ProfLibunwind;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_libunwind = ProfLibunwind::new().unwrap();
+///
+///     println!("prof libunwind: {}", prof_libunwind.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfLibunwind(Mib<bool, [usize; 2]>);
+
+impl ProfLibunwind {
+    /// Returns a new `ProfLibunwind`.
+    pub fn new() -> Result<ProfLibunwind> {
+        unsafe { Mib::new(PROF_LIBUNWIND).map(ProfLibunwind) }
+    }
+
+    /// Returns the prof libunwind build configuration.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const STATS: &[u8] = b"config.stats\0";
+
+/// Determines if `--enable-stats` was specified during build configuration.
+///
+/// This enables support for statistics, including the operations in the `stats` module.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("stats: {}", jemalloc_ctl::config::stats().unwrap());
+/// }
+/// ```
+pub fn stats() -> Result<bool> {
+    unsafe { get(STATS) }
+}
+
+/// A type determining if `--enable-stats` was specified during build configuration.
+///
+/// This enables support for statistics, including the operations in the `stats` module.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::Stats;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let stats = Stats::new().unwrap();
+///
+///     println!("stats: {}", stats.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Stats(Mib<bool, [usize; 2]>);
+
+impl Stats {
+    /// Returns a new `Stats`.
+    pub fn new() -> Result<Stats> {
+        unsafe { Mib::new(STATS).map(Stats) }
+    }
+
+    /// Returns the stats build configuration.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const UTRACE: &[u8] = b"config.utrace\0";
+
+/// Determines if `--enable-utrace` was specified during build configuration.
+///
+/// This enables support for tracing allocation calls with `utrace(2)`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("utrace: {}", jemalloc_ctl::config::utrace().unwrap());
+/// }
+/// ```
+pub fn utrace() -> Result<bool> {
+    unsafe { get(UTRACE) }
+}
+
+/// A type determining if `--enable-utrace` was specified during build configuration.
+///
+/// This enables support for tracing allocation calls with `utrace(2)`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::Utrace;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let utrace = Utrace::new().unwrap();
+///
+///     println!("utrace: {}", utrace.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Utrace(Mib<bool, [usize; 2]>);
+
+impl Utrace {
+    /// Returns a new `Utrace`.
+    pub fn new() -> Result<Utrace> {
+        unsafe { Mib::new(UTRACE).map(Utrace) }
+    }
+
+    /// Returns the utrace build configuration.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const XMALLOC: &[u8] = b"config.xmalloc\0";
+
+/// Determines if `--enable-xmalloc` was specified during build configuration.
+///
+/// This enables support for the `xmalloc` option.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("xmalloc: {}", jemalloc_ctl::config::xmalloc().unwrap());
+/// }
+/// ```
+pub fn xmalloc() -> Result<bool> {
+    unsafe { get(XMALLOC) }
+}
+
+/// A type determining if `--enable-xmalloc` was specified during build configuration.
+///
+/// This enables support for the `xmalloc` option.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::config::Xmalloc;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let xmalloc = Xmalloc::new().unwrap();
+///
+///     println!("xmalloc: {}", xmalloc.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Xmalloc(Mib<bool, [usize; 2]>);
+
+impl Xmalloc {
+    /// Returns a new `Xmalloc`.
+    pub fn new() -> Result<Xmalloc> {
+        unsafe { Mib::new(XMALLOC).map(Xmalloc) }
+    }
+
+    /// Returns the xmalloc build configuration.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+/// The features jemalloc was built with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Capabilities {
+    /// Whether `--enable-debug` was specified during build configuration.
+    pub debug: bool,
+    /// Whether `--enable-fill` was specified during build configuration.
+    pub fill: bool,
+    /// Whether `--enable-lazy-lock` was specified during build configuration.
+    pub lazy_lock: bool,
+    /// Whether `--enable-prof` was specified during build configuration.
+    pub prof: bool,
+    /// Whether `--disable-prof-libgcc` was not specified during build configuration.
+    pub prof_libgcc: bool,
+    /// Whether `--enable-prof-libunwind` was specified during build configuration.
+    pub prof_libunwind: bool,
+    /// Whether `--enable-stats` was specified during build configuration.
+    pub stats: bool,
+    /// Whether `--enable-utrace` was specified during build configuration.
+    pub utrace: bool,
+    /// Whether `--enable-xmalloc` was specified during build configuration.
+    pub xmalloc: bool,
+}
+
+/// Returns the features jemalloc was built with.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let capabilities = jemalloc_ctl::config::capabilities().unwrap();
+///
+///     if capabilities.stats {
+///         println!("allocated: {}", jemalloc_ctl::stats::allocated().unwrap());
+///     } else {
+///         println!("statistics are not available");
+///     }
+/// }
+/// ```
+pub fn capabilities() -> Result<Capabilities> {
+    Ok(Capabilities {
+        debug: debug()?,
+        fill: fill()?,
+        lazy_lock: lazy_lock()?,
+        prof: prof()?,
+        prof_libgcc: prof_libgcc()?,
+        prof_libunwind: prof_libunwind()?,
+        stats: stats()?,
+        utrace: utrace()?,
+        xmalloc: xmalloc()?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn capabilities_match() {
+        let capabilities = capabilities().unwrap();
+        assert_eq!(capabilities.prof, prof().unwrap());
+        assert_eq!(capabilities.stats, stats().unwrap());
+
+        if !capabilities.stats {
+            assert!(::stats::allocated().is_err());
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Conversion of string arguments to the C strings jemalloc expects.
+use std::borrow::Cow;
+use std::ffi::{CStr, CString, OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+/// A value which can be passed to string-valued `mallctl` operations, such as `prof.dump` or
+/// `arena.<i>.dss`.
+///
+/// jemalloc takes these values as NUL-terminated `const char *` strings. A `&CStr` is passed as
+/// is, while other strings are copied into a temporary `CString`. The conversion fails, and the
+/// operation returns an [`Error::InvalidValue`] error, if the string contains an interior NUL
+/// byte.
+///
+/// Paths and OS strings are passed as raw bytes on Unix platforms, and must be valid Unicode
+/// elsewhere.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::ToCStr;
+/// use std::path::Path;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let path = Path::new("/tmp/jeprof.heap");
+///     assert_eq!(path.to_c_str().unwrap().to_bytes(), b"/tmp/jeprof.heap");
+///
+///     assert!("foo\0bar".to_c_str().is_none());
+/// }
+/// ```
+///
+/// [`Error::InvalidValue`]: enum.Error.html#variant.InvalidValue
+pub trait ToCStr {
+    /// Returns the value as a C string, or `None` if it cannot be represented as one.
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>>;
+}
+
+impl ToCStr for CStr {
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl ToCStr for CString {
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        Some(Cow::Borrowed(self.as_c_str()))
+    }
+}
+
+impl ToCStr for str {
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        CString::new(self).ok().map(Cow::Owned)
+    }
+}
+
+impl ToCStr for String {
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        self.as_str().to_c_str()
+    }
+}
+
+#[cfg(unix)]
+impl ToCStr for OsStr {
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        use std::os::unix::ffi::OsStrExt;
+
+        CString::new(self.as_bytes()).ok().map(Cow::Owned)
+    }
+}
+
+#[cfg(not(unix))]
+impl ToCStr for OsStr {
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        self.to_str().and_then(ToCStr::to_c_str)
+    }
+}
+
+impl ToCStr for OsString {
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        self.as_os_str().to_c_str()
+    }
+}
+
+impl ToCStr for Path {
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        self.as_os_str().to_c_str()
+    }
+}
+
+impl ToCStr for PathBuf {
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        self.as_os_str().to_c_str()
+    }
+}
+
+impl<T> ToCStr for &T
+where
+    T: ToCStr + ?Sized,
+{
+    fn to_c_str(&self) -> Option<Cow<'_, CStr>> {
+        (**self).to_c_str()
+    }
+}
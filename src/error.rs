@@ -0,0 +1,266 @@
+//! Error types.
+use jemalloc_sys;
+use libc::{self, c_int};
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+use std::str::Utf8Error;
+
+/// The result type returned by `mallctl` operations.
+pub type Result<T> = result::Result<T, Error>;
+
+/// An error returned by a `mallctl` operation.
+///
+/// Each variant carries the name of the operation which failed. `Error` can be converted into an
+/// `io::Error`, so code written against earlier versions of this crate which used `io::Result`
+/// continues to work with the `?` operator.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Error;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     match unsafe { jemalloc_ctl::raw::read::<usize>(b"stats.bogus\0") } {
+///         Err(Error::UnknownName { ref name }) => assert_eq!(name, "stats.bogus"),
+///         r => panic!("unexpected result {:?}", r),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// The name does not correspond to an operation, or contains an out of range index.
+    ///
+    /// This corresponds to an `ENOENT` error for a name jemalloc cannot resolve.
+    UnknownName {
+        /// The name of the operation.
+        name: String,
+    },
+
+    /// The operation exists, but jemalloc was built without the feature it depends on.
+    ///
+    /// This corresponds to an `ENOENT` error for a name jemalloc can resolve, such as `prof.*`
+    /// operations when jemalloc was configured without `--enable-prof`.
+    NotConfigured {
+        /// The name of the operation.
+        name: String,
+    },
+
+    /// The name is not NUL-terminated, contains interior NUL bytes, or does not have the expected
+    /// number of components.
+    InvalidName {
+        /// The name of the operation.
+        name: String,
+    },
+
+    /// The value passed to the operation is invalid, or does not have the size jemalloc expects.
+    ///
+    /// This corresponds to an `EINVAL` error.
+    InvalidValue {
+        /// The name of the operation.
+        name: String,
+    },
+
+    /// The operation cannot be performed in the requested direction, such as writing to a
+    /// read-only operation.
+    ///
+    /// This corresponds to an `EPERM` error.
+    NotPermitted {
+        /// The name of the operation.
+        name: String,
+    },
+
+    /// The operation is unsupported in the current context, or otherwise failed in some way not
+    /// directly related to reading or writing its value.
+    ///
+    /// This corresponds to an `EFAULT` error.
+    Unsupported {
+        /// The name of the operation.
+        name: String,
+    },
+
+    /// The operation could not be performed right now, due to contention or a resource limit, and
+    /// may succeed if retried.
+    ///
+    /// This corresponds to an `EAGAIN` error, such as `arenas.create` failing to create an arena
+    /// or `background_thread` failing to start a thread.
+    Busy {
+        /// The name of the operation.
+        name: String,
+    },
+
+    /// jemalloc failed to allocate memory while performing the operation.
+    ///
+    /// This corresponds to an `ENOMEM` error.
+    OutOfMemory {
+        /// The name of the operation.
+        name: String,
+    },
+
+    /// The operation returned a string which is not valid UTF-8.
+    Utf8 {
+        /// The name of the operation.
+        name: String,
+        /// The underlying error.
+        error: Utf8Error,
+    },
+
+    /// The operation returned a string which does not correspond to any known value, such as a mode
+    /// added in a newer version of jemalloc.
+    UnexpectedValue {
+        /// The name of the operation.
+        name: String,
+        /// The string jemalloc returned.
+        value: String,
+    },
+
+    /// jemalloc returned a value of a different size than the requested type.
+    SizeMismatch {
+        /// The name of the operation.
+        name: String,
+        /// The size of the requested type.
+        expected: usize,
+        /// The size of the value jemalloc returned.
+        actual: usize,
+    },
+
+    /// The operation failed with an unrecognized error code.
+    Other {
+        /// The name of the operation.
+        name: String,
+        /// The error code.
+        code: c_int,
+    },
+}
+
+impl Error {
+    /// Returns the name of the operation which failed.
+    pub fn name(&self) -> &str {
+        match *self {
+            Error::UnknownName { ref name }
+            | Error::NotConfigured { ref name }
+            | Error::InvalidName { ref name }
+            | Error::InvalidValue { ref name }
+            | Error::NotPermitted { ref name }
+            | Error::Unsupported { ref name }
+            | Error::Busy { ref name }
+            | Error::OutOfMemory { ref name }
+            | Error::Utf8 { ref name, .. }
+            | Error::UnexpectedValue { ref name, .. }
+            | Error::SizeMismatch { ref name, .. }
+            | Error::Other { ref name, .. } => name,
+        }
+    }
+
+    pub(crate) fn from_code(code: c_int, name: String) -> Error {
+        match code {
+            libc::ENOENT => {
+                if resolves(&name) {
+                    Error::NotConfigured { name }
+                } else {
+                    Error::UnknownName { name }
+                }
+            }
+            libc::EINVAL => Error::InvalidValue { name },
+            libc::EPERM => Error::NotPermitted { name },
+            libc::EFAULT => Error::Unsupported { name },
+            libc::EAGAIN => Error::Busy { name },
+            libc::ENOMEM => Error::OutOfMemory { name },
+            code => Error::Other { name, code },
+        }
+    }
+}
+
+// Determines if jemalloc recognizes the name, which distinguishes unknown operations from those
+// which are compiled out.
+fn resolves(name: &str) -> bool {
+    let mut name = name.as_bytes().to_vec();
+    name.push(0);
+    let mut mib = [0; 8];
+    let mut len = mib.len();
+    unsafe {
+        jemalloc_sys::mallctlnametomib(name.as_ptr() as *const _, mib.as_mut_ptr(), &mut len) == 0
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::UnknownName { ref name } => write!(fmt, "unknown mallctl name `{}`", name),
+            Error::NotConfigured { ref name } => {
+                write!(fmt, "`{}` is not enabled in this jemalloc build", name)
+            }
+            Error::InvalidName { ref name } => write!(fmt, "invalid mallctl name `{}`", name),
+            Error::InvalidValue { ref name } => write!(fmt, "invalid value for `{}`", name),
+            Error::NotPermitted { ref name } => {
+                write!(fmt, "`{}` does not permit the requested access", name)
+            }
+            Error::Unsupported { ref name } => write!(fmt, "`{}` failed or is unsupported", name),
+            Error::Busy { ref name } => {
+                write!(fmt, "`{}` is temporarily unavailable", name)
+            }
+            Error::OutOfMemory { ref name } => {
+                write!(fmt, "jemalloc ran out of memory performing `{}`", name)
+            }
+            Error::Utf8 {
+                ref name,
+                ref error,
+            } => write!(
+                fmt,
+                "`{}` returned an invalid UTF-8 string: {}",
+                name, error
+            ),
+            Error::UnexpectedValue {
+                ref name,
+                ref value,
+            } => write!(fmt, "`{}` returned an unrecognized value `{}`", name, value),
+            Error::SizeMismatch {
+                ref name,
+                expected,
+                actual,
+            } => write!(
+                fmt,
+                "`{}` returned a {} byte value but {} bytes were expected",
+                name, actual, expected
+            ),
+            Error::Other { ref name, code } => write!(
+                fmt,
+                "`{}` failed: {}",
+                name,
+                io::Error::from_raw_os_error(code)
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Utf8 { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> io::Error {
+        let kind = match e {
+            Error::UnknownName { .. } | Error::NotConfigured { .. } => io::ErrorKind::NotFound,
+            Error::InvalidName { .. } | Error::InvalidValue { .. } => io::ErrorKind::InvalidInput,
+            Error::NotPermitted { .. } => io::ErrorKind::PermissionDenied,
+            Error::Busy { .. } => io::ErrorKind::WouldBlock,
+            Error::Utf8 { .. } | Error::UnexpectedValue { .. } | Error::SizeMismatch { .. } => {
+                io::ErrorKind::InvalidData
+            }
+            _ => io::ErrorKind::Other,
+        };
+        io::Error::new(kind, e)
+    }
+}
@@ -0,0 +1,652 @@
+//! Custom extent management.
+//!
+//! jemalloc obtains memory from the operating system in large chunks called extents. An arena can
+//! be given a custom set of extent hooks which replace the functions jemalloc uses to allocate,
+//! deallocate, commit, decommit, purge, split and merge extents, allowing it to be backed by
+//! memory from an alternative source such as reserved hugepages or a memory-mapped file.
+//!
+//! Hooks are implemented through the [`ExtentHooks`] trait, and installed with
+//! [`arenas::create_with_hooks`] or [`arenas::set_extent_hooks`].
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate jemallocator;
+//! extern crate jemalloc_ctl;
+//! extern crate jemalloc_sys;
+//! extern crate libc;
+//!
+//! use jemalloc_ctl::arenas;
+//! use jemalloc_ctl::extent_hooks::ExtentHooks;
+//! use std::os::raw::c_uint;
+//! use std::ptr;
+//!
+//! #[global_allocator]
+//! static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+//!
+//! // Allocates extents with anonymous mappings.
+//! struct Mmap;
+//!
+//! unsafe impl ExtentHooks for Mmap {
+//!     fn alloc(
+//!         &self,
+//!         new_addr: *mut u8,
+//!         size: usize,
+//!         alignment: usize,
+//!         zero: &mut bool,
+//!         commit: &mut bool,
+//!         _: c_uint,
+//!     ) -> *mut u8 {
+//!         if !new_addr.is_null() {
+//!             return ptr::null_mut();
+//!         }
+//!
+//!         unsafe {
+//!             // over-allocate so the mapping can be trimmed to the requested alignment
+//!             let len = size + alignment;
+//!             let base = libc::mmap(
+//!                 ptr::null_mut(),
+//!                 len,
+//!                 libc::PROT_READ | libc::PROT_WRITE,
+//!                 libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+//!                 -1,
+//!                 0,
+//!             );
+//!             if base == libc::MAP_FAILED {
+//!                 return ptr::null_mut();
+//!             }
+//!
+//!             let base = base as usize;
+//!             let addr = (base + alignment - 1) & !(alignment - 1);
+//!             if addr > base {
+//!                 libc::munmap(base as *mut _, addr - base);
+//!             }
+//!             if base + len > addr + size {
+//!                 libc::munmap((addr + size) as *mut _, base + len - addr - size);
+//!             }
+//!
+//!             *zero = true;
+//!             *commit = true;
+//!             addr as *mut u8
+//!         }
+//!     }
+//!
+//!     fn dalloc(&self, addr: *mut u8, size: usize, _: bool, _: c_uint) -> bool {
+//!         unsafe { libc::munmap(addr as *mut _, size) == 0 }
+//!     }
+//!
+//!     fn destroy(&self, addr: *mut u8, size: usize, _: bool, _: c_uint) {
+//!         unsafe {
+//!             libc::munmap(addr as *mut _, size);
+//!         }
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let arena = arenas::create_with_hooks(Mmap).unwrap();
+//!
+//!     unsafe {
+//!         let ptr = jemalloc_sys::mallocx(1024 * 1024, arena.flags());
+//!         assert!(!ptr.is_null());
+//!         jemalloc_sys::dallocx(ptr, arena.flags());
+//!
+//!         arena.destroy().unwrap();
+//!     }
+//! }
+//! ```
+//!
+//! [`ExtentHooks`]: trait.ExtentHooks.html
+//! [`arenas::create_with_hooks`]: ../arenas/fn.create_with_hooks.html
+//! [`arenas::set_extent_hooks`]: ../arenas/fn.set_extent_hooks.html
+
+use jemalloc_sys::extent_hooks_t;
+use libc::{c_uint, c_void, size_t};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+/// A set of functions managing the extents of an arena.
+///
+/// Except for `alloc`, every operation may be declined by returning `false` (or doing nothing, in
+/// the case of `destroy`), in which case jemalloc falls back to a less permanent operation or
+/// retains the extent for later reuse. The default implementations decline every operation.
+///
+/// Addresses and sizes passed to the hooks are always multiples of the page size. The `arena`
+/// argument of each method is the index of the arena on whose behalf the operation is performed.
+///
+/// If a method panics, the panic is caught and the operation is reported to jemalloc as having
+/// failed. Unwinding cannot continue into jemalloc, so the panic is not otherwise propagated.
+///
+/// # Safety
+///
+/// jemalloc trusts the memory returned by `alloc`. Implementations must return either a null
+/// pointer or a pointer to `size` bytes of readable and writable memory (if committed) aligned to
+/// `alignment`, which is not used for anything else until it is passed to `dalloc` or `destroy`.
+/// The `zero` and `commit` flags must accurately describe the memory returned.
+pub unsafe trait ExtentHooks: Send + Sync + 'static {
+    /// Allocates an extent of `size` bytes aligned to `alignment`.
+    ///
+    /// If `new_addr` is not null, the extent must be placed at that address, or the allocation
+    /// must fail. On entry, `zero` and `commit` indicate whether the extent must be zeroed and
+    /// committed. If the extent is zeroed or committed regardless, the hook should set them to
+    /// `true`.
+    ///
+    /// Returns a null pointer on failure.
+    fn alloc(
+        &self,
+        new_addr: *mut u8,
+        size: usize,
+        alignment: usize,
+        zero: &mut bool,
+        commit: &mut bool,
+        arena: c_uint,
+    ) -> *mut u8;
+
+    /// Deallocates an extent.
+    ///
+    /// Returns `true` if the extent was deallocated. If the hook declines, jemalloc retains the
+    /// extent for later reuse.
+    fn dalloc(&self, addr: *mut u8, size: usize, committed: bool, arena: c_uint) -> bool {
+        let _ = (addr, size, committed, arena);
+        false
+    }
+
+    /// Unconditionally destroys an extent.
+    ///
+    /// This is called for retained extents when their arena is destroyed.
+    fn destroy(&self, addr: *mut u8, size: usize, committed: bool, arena: c_uint) {
+        let _ = (addr, size, committed, arena);
+    }
+
+    /// Commits `length` bytes of an extent at `offset`, backing them with physical memory.
+    ///
+    /// Returns `true` if the pages were committed.
+    fn commit(
+        &self,
+        addr: *mut u8,
+        size: usize,
+        offset: usize,
+        length: usize,
+        arena: c_uint,
+    ) -> bool {
+        let _ = (addr, size, offset, length, arena);
+        false
+    }
+
+    /// Decommits `length` bytes of an extent at `offset`, releasing their physical memory.
+    ///
+    /// Returns `true` if the pages were decommitted.
+    fn decommit(
+        &self,
+        addr: *mut u8,
+        size: usize,
+        offset: usize,
+        length: usize,
+        arena: c_uint,
+    ) -> bool {
+        let _ = (addr, size, offset, length, arena);
+        false
+    }
+
+    /// Lazily purges `length` bytes of an extent at `offset`, allowing the operating system to
+    /// reclaim them at its convenience.
+    ///
+    /// Returns `true` if the pages were purged.
+    fn purge_lazy(
+        &self,
+        addr: *mut u8,
+        size: usize,
+        offset: usize,
+        length: usize,
+        arena: c_uint,
+    ) -> bool {
+        let _ = (addr, size, offset, length, arena);
+        false
+    }
+
+    /// Purges `length` bytes of an extent at `offset`, such that they read as zero afterwards.
+    ///
+    /// Returns `true` if the pages were purged.
+    fn purge_forced(
+        &self,
+        addr: *mut u8,
+        size: usize,
+        offset: usize,
+        length: usize,
+        arena: c_uint,
+    ) -> bool {
+        let _ = (addr, size, offset, length, arena);
+        false
+    }
+
+    /// Splits an extent into two adjacent extents of `size_a` and `size_b` bytes.
+    ///
+    /// Returns `true` if the extent can be split.
+    fn split(
+        &self,
+        addr: *mut u8,
+        size: usize,
+        size_a: usize,
+        size_b: usize,
+        committed: bool,
+        arena: c_uint,
+    ) -> bool {
+        let _ = (addr, size, size_a, size_b, committed, arena);
+        false
+    }
+
+    /// Merges two adjacent extents into one.
+    ///
+    /// Returns `true` if the extents can be merged.
+    fn merge(
+        &self,
+        addr_a: *mut u8,
+        size_a: usize,
+        addr_b: *mut u8,
+        size_b: usize,
+        committed: bool,
+        arena: c_uint,
+    ) -> bool {
+        let _ = (addr_a, size_a, addr_b, size_b, committed, arena);
+        false
+    }
+}
+
+// jemalloc's `extent_hooks_t`. jemalloc_sys declares the `bool` parameters as `c_int`, which does
+// not match the C definition, so the vtable is defined here and cast when it is handed to jemalloc.
+#[repr(C)]
+struct RawExtentHooks {
+    alloc: unsafe extern "C" fn(
+        *mut RawExtentHooks,
+        *mut c_void,
+        size_t,
+        size_t,
+        *mut bool,
+        *mut bool,
+        c_uint,
+    ) -> *mut c_void,
+    dalloc: unsafe extern "C" fn(*mut RawExtentHooks, *mut c_void, size_t, bool, c_uint) -> bool,
+    destroy: unsafe extern "C" fn(*mut RawExtentHooks, *mut c_void, size_t, bool, c_uint),
+    commit: unsafe extern "C" fn(
+        *mut RawExtentHooks,
+        *mut c_void,
+        size_t,
+        size_t,
+        size_t,
+        c_uint,
+    ) -> bool,
+    decommit: unsafe extern "C" fn(
+        *mut RawExtentHooks,
+        *mut c_void,
+        size_t,
+        size_t,
+        size_t,
+        c_uint,
+    ) -> bool,
+    purge_lazy: unsafe extern "C" fn(
+        *mut RawExtentHooks,
+        *mut c_void,
+        size_t,
+        size_t,
+        size_t,
+        c_uint,
+    ) -> bool,
+    purge_forced: unsafe extern "C" fn(
+        *mut RawExtentHooks,
+        *mut c_void,
+        size_t,
+        size_t,
+        size_t,
+        c_uint,
+    ) -> bool,
+    split: unsafe extern "C" fn(
+        *mut RawExtentHooks,
+        *mut c_void,
+        size_t,
+        size_t,
+        size_t,
+        bool,
+        c_uint,
+    ) -> bool,
+    merge: unsafe extern "C" fn(
+        *mut RawExtentHooks,
+        *mut c_void,
+        size_t,
+        *mut c_void,
+        size_t,
+        bool,
+        c_uint,
+    ) -> bool,
+}
+
+// The vtable must be the first field, since jemalloc passes a pointer to it back to each hook.
+#[repr(C)]
+struct Hooks<H> {
+    raw: RawExtentHooks,
+    hooks: H,
+}
+
+// Returns a pointer to a vtable for the hooks. The allocation is intentionally leaked unless it is
+// released with `free_raw`, since jemalloc requires hooks to remain valid for the lifetime of the
+// arena.
+pub(crate) fn into_raw<H>(hooks: H) -> *mut extent_hooks_t
+where
+    H: ExtentHooks,
+{
+    let hooks = Box::new(Hooks {
+        raw: RawExtentHooks {
+            alloc: alloc::<H>,
+            dalloc: dalloc::<H>,
+            destroy: destroy::<H>,
+            commit: commit::<H>,
+            decommit: decommit::<H>,
+            purge_lazy: purge_lazy::<H>,
+            purge_forced: purge_forced::<H>,
+            split: split::<H>,
+            merge: merge::<H>,
+        },
+        hooks,
+    });
+    Box::into_raw(hooks) as *mut extent_hooks_t
+}
+
+// Releases a vtable returned by `into_raw` which was never installed in an arena.
+pub(crate) unsafe fn free_raw<H>(raw: *mut extent_hooks_t)
+where
+    H: ExtentHooks,
+{
+    drop(Box::from_raw(raw as *mut Hooks<H>));
+}
+
+unsafe fn hooks<'a, H>(raw: *mut RawExtentHooks) -> &'a H {
+    &(*(raw as *const Hooks<H>)).hooks
+}
+
+// Runs a hook, treating a panic as a failure.
+fn catch<F, T>(f: F, failure: T) -> T
+where
+    F: FnOnce() -> T,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(failure)
+}
+
+unsafe extern "C" fn alloc<H>(
+    raw: *mut RawExtentHooks,
+    new_addr: *mut c_void,
+    size: size_t,
+    alignment: size_t,
+    zero: *mut bool,
+    commit: *mut bool,
+    arena: c_uint,
+) -> *mut c_void
+where
+    H: ExtentHooks,
+{
+    let hooks = hooks::<H>(raw);
+    catch(
+        || {
+            hooks.alloc(
+                new_addr as *mut u8,
+                size,
+                alignment,
+                &mut *zero,
+                &mut *commit,
+                arena,
+            ) as *mut c_void
+        },
+        ptr::null_mut(),
+    )
+}
+
+// jemalloc's hooks return `false` on success, so the results of the trait methods are inverted.
+
+unsafe extern "C" fn dalloc<H>(
+    raw: *mut RawExtentHooks,
+    addr: *mut c_void,
+    size: size_t,
+    committed: bool,
+    arena: c_uint,
+) -> bool
+where
+    H: ExtentHooks,
+{
+    let hooks = hooks::<H>(raw);
+    !catch(
+        || hooks.dalloc(addr as *mut u8, size, committed, arena),
+        false,
+    )
+}
+
+unsafe extern "C" fn destroy<H>(
+    raw: *mut RawExtentHooks,
+    addr: *mut c_void,
+    size: size_t,
+    committed: bool,
+    arena: c_uint,
+) where
+    H: ExtentHooks,
+{
+    let hooks = hooks::<H>(raw);
+    catch(
+        || hooks.destroy(addr as *mut u8, size, committed, arena),
+        (),
+    )
+}
+
+unsafe extern "C" fn commit<H>(
+    raw: *mut RawExtentHooks,
+    addr: *mut c_void,
+    size: size_t,
+    offset: size_t,
+    length: size_t,
+    arena: c_uint,
+) -> bool
+where
+    H: ExtentHooks,
+{
+    let hooks = hooks::<H>(raw);
+    !catch(
+        || hooks.commit(addr as *mut u8, size, offset, length, arena),
+        false,
+    )
+}
+
+unsafe extern "C" fn decommit<H>(
+    raw: *mut RawExtentHooks,
+    addr: *mut c_void,
+    size: size_t,
+    offset: size_t,
+    length: size_t,
+    arena: c_uint,
+) -> bool
+where
+    H: ExtentHooks,
+{
+    let hooks = hooks::<H>(raw);
+    !catch(
+        || hooks.decommit(addr as *mut u8, size, offset, length, arena),
+        false,
+    )
+}
+
+unsafe extern "C" fn purge_lazy<H>(
+    raw: *mut RawExtentHooks,
+    addr: *mut c_void,
+    size: size_t,
+    offset: size_t,
+    length: size_t,
+    arena: c_uint,
+) -> bool
+where
+    H: ExtentHooks,
+{
+    let hooks = hooks::<H>(raw);
+    !catch(
+        || hooks.purge_lazy(addr as *mut u8, size, offset, length, arena),
+        false,
+    )
+}
+
+unsafe extern "C" fn purge_forced<H>(
+    raw: *mut RawExtentHooks,
+    addr: *mut c_void,
+    size: size_t,
+    offset: size_t,
+    length: size_t,
+    arena: c_uint,
+) -> bool
+where
+    H: ExtentHooks,
+{
+    let hooks = hooks::<H>(raw);
+    !catch(
+        || hooks.purge_forced(addr as *mut u8, size, offset, length, arena),
+        false,
+    )
+}
+
+unsafe extern "C" fn split<H>(
+    raw: *mut RawExtentHooks,
+    addr: *mut c_void,
+    size: size_t,
+    size_a: size_t,
+    size_b: size_t,
+    committed: bool,
+    arena: c_uint,
+) -> bool
+where
+    H: ExtentHooks,
+{
+    let hooks = hooks::<H>(raw);
+    !catch(
+        || hooks.split(addr as *mut u8, size, size_a, size_b, committed, arena),
+        false,
+    )
+}
+
+unsafe extern "C" fn merge<H>(
+    raw: *mut RawExtentHooks,
+    addr_a: *mut c_void,
+    size_a: size_t,
+    addr_b: *mut c_void,
+    size_b: size_t,
+    committed: bool,
+    arena: c_uint,
+) -> bool
+where
+    H: ExtentHooks,
+{
+    let hooks = hooks::<H>(raw);
+    !catch(
+        || {
+            hooks.merge(
+                addr_a as *mut u8,
+                size_a,
+                addr_b as *mut u8,
+                size_b,
+                committed,
+                arena,
+            )
+        },
+        false,
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use arenas;
+    use jemalloc_sys;
+    use libc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct Counting {
+        allocs: Arc<AtomicUsize>,
+    }
+
+    unsafe impl ExtentHooks for Counting {
+        fn alloc(
+            &self,
+            new_addr: *mut u8,
+            size: usize,
+            alignment: usize,
+            zero: &mut bool,
+            commit: &mut bool,
+            _: c_uint,
+        ) -> *mut u8 {
+            if !new_addr.is_null() {
+                return ptr::null_mut();
+            }
+            self.allocs.fetch_add(1, Ordering::SeqCst);
+            unsafe {
+                let mut ptr = ptr::null_mut();
+                if libc::posix_memalign(&mut ptr, alignment, size) != 0 {
+                    return ptr::null_mut();
+                }
+                libc::memset(ptr, 0, size);
+                *zero = true;
+                *commit = true;
+                ptr as *mut u8
+            }
+        }
+    }
+
+    struct Panicking;
+
+    unsafe impl ExtentHooks for Panicking {
+        fn alloc(
+            &self,
+            _: *mut u8,
+            _: usize,
+            _: usize,
+            _: &mut bool,
+            _: &mut bool,
+            _: c_uint,
+        ) -> *mut u8 {
+            panic!("boom")
+        }
+    }
+
+    #[test]
+    fn allocations_use_hooks() {
+        let allocs = Arc::new(AtomicUsize::new(0));
+        let arena = arenas::create_with_hooks(Counting {
+            allocs: allocs.clone(),
+        })
+        .unwrap();
+        let before = allocs.load(Ordering::SeqCst);
+
+        unsafe {
+            let ptr = jemalloc_sys::mallocx(8 * 1024 * 1024, arena.flags());
+            assert!(!ptr.is_null());
+            jemalloc_sys::dallocx(ptr, arena.flags());
+        }
+        assert!(allocs.load(Ordering::SeqCst) > before);
+    }
+
+    #[test]
+    fn replace_hooks() {
+        let allocs = Arc::new(AtomicUsize::new(0));
+        let arena = arenas::create().unwrap();
+
+        unsafe {
+            arenas::set_extent_hooks(
+                arena.index(),
+                Counting {
+                    allocs: allocs.clone(),
+                },
+            )
+            .unwrap();
+
+            let ptr = jemalloc_sys::mallocx(8 * 1024 * 1024, arena.flags());
+            assert!(!ptr.is_null());
+            jemalloc_sys::dallocx(ptr, arena.flags());
+        }
+        assert!(allocs.load(Ordering::SeqCst) > 0);
+    }
+
+    #[test]
+    fn panic_is_failure() {
+        assert!(arenas::create_with_hooks(Panicking).is_err());
+    }
+}
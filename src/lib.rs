@@ -14,6 +14,9 @@
 //! performed. Its constructor performs the MIB lookup, so the struct should be saved if the same
 //! operation is going to be repeatedly performed.
 //!
+//! Operations which don't have a dedicated function or type can be accessed through the generic
+//! [`Mib`] type, or by name through the functions in the [`raw`] module.
+//!
 //! # Examples
 //!
 //! Repeatedly printing allocation statistics:
@@ -68,6 +71,9 @@
 //!     }
 //! }
 //! ```
+//!
+//! [`Mib`]: struct.Mib.html
+//! [`raw`]: raw/index.html
 #![doc(html_root_url = "https://docs.rs/jemalloc-ctl/0.1")]
 #![warn(missing_docs)]
 
@@ -82,129 +88,272 @@ extern crate jemallocator;
 static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 
 use libc::{c_char, c_int};
+use std::borrow::Cow;
 use std::ffi::CStr;
-use std::io;
-use std::mem;
+use std::mem::{self, MaybeUninit};
 use std::ptr;
 
+pub use cstr::ToCStr;
+pub use error::{Error, Result};
+pub use mib::{Mib, MibLen, MibStr};
+
 pub mod arenas;
 pub mod config;
+mod cstr;
+mod error;
+pub mod extent_hooks;
+mod mib;
 pub mod opt;
+pub mod prof;
+pub mod raw;
 pub mod stats;
 pub mod stats_print;
+pub mod tcache;
 pub mod thread;
 
-unsafe fn name_to_mib(name: *const c_char, mib: &mut [usize]) -> io::Result<()> {
+fn name_ptr(name: &[u8]) -> Result<*const c_char> {
+    match name.iter().position(|&b| b == 0) {
+        Some(i) if i == name.len() - 1 => Ok(name.as_ptr() as *const c_char),
+        _ => Err(Error::InvalidName {
+            name: name_string(name),
+        }),
+    }
+}
+
+fn name_string(name: &[u8]) -> String {
+    let name = match name.split_last() {
+        Some((&0, name)) => name,
+        _ => name,
+    };
+    String::from_utf8_lossy(name).into_owned()
+}
+
+unsafe fn name_to_mib(name: &[u8], mib: &mut [usize]) -> Result<()> {
     let mut len = mib.len();
-    cvt(jemalloc_sys::mallctlnametomib(
-        name,
-        mib.as_mut_ptr(),
-        &mut len,
-    ))?;
-    debug_assert_eq!(mib.len(), len);
+    cvt(
+        jemalloc_sys::mallctlnametomib(name_ptr(name)?, mib.as_mut_ptr(), &mut len),
+        &|| name_string(name),
+    )?;
+    if len != mib.len() {
+        return Err(Error::InvalidName {
+            name: name_string(name),
+        });
+    }
     Ok(())
 }
 
-unsafe fn get_mib<T>(mib: &[usize]) -> io::Result<T> {
-    let mut value = mem::uninitialized::<T>();
+// jemalloc rejects an `oldlen` which doesn't match the size of the operation's value with
+// `EINVAL`, so a type size mismatch is usually reported as `InvalidValue`. `check_len` is a
+// fallback for operations which write a shorter value than requested.
+unsafe fn get_mib<T>(mib: &[usize], name: &dyn Fn() -> String) -> Result<T> {
+    let mut value = MaybeUninit::<T>::uninit();
     let mut len = mem::size_of::<T>();
-    cvt(jemalloc_sys::mallctlbymib(
-        mib.as_ptr(),
-        mib.len(),
-        &mut value as *mut _ as *mut _,
-        &mut len,
-        ptr::null_mut(),
-        0,
-    ))?;
-    debug_assert_eq!(len, mem::size_of::<T>());
-    Ok(value)
+    cvt(
+        jemalloc_sys::mallctlbymib(
+            mib.as_ptr(),
+            mib.len(),
+            value.as_mut_ptr() as *mut _,
+            &mut len,
+            ptr::null_mut(),
+            0,
+        ),
+        name,
+    )?;
+    check_len::<T>(len, name)?;
+    Ok(value.assume_init())
 }
 
-unsafe fn get<T>(name: *const c_char) -> io::Result<T> {
-    let mut value = mem::uninitialized::<T>();
+unsafe fn get<T>(name: &[u8]) -> Result<T> {
+    let mut value = MaybeUninit::<T>::uninit();
     let mut len = mem::size_of::<T>();
-    cvt(jemalloc_sys::mallctl(
-        name,
-        &mut value as *mut _ as *mut _,
-        &mut len,
-        ptr::null_mut(),
-        0,
-    ))?;
-    debug_assert_eq!(len, mem::size_of::<T>());
-    Ok(value)
+    cvt(
+        jemalloc_sys::mallctl(
+            name_ptr(name)?,
+            value.as_mut_ptr() as *mut _,
+            &mut len,
+            ptr::null_mut(),
+            0,
+        ),
+        &|| name_string(name),
+    )?;
+    check_len::<T>(len, &|| name_string(name))?;
+    Ok(value.assume_init())
 }
 
-unsafe fn get_str_mib(mib: &[usize]) -> io::Result<&'static str> {
-    let ptr: *const c_char = get_mib(mib)?;
+unsafe fn get_str_mib(mib: &[usize], name: &dyn Fn() -> String) -> Result<&'static str> {
+    let ptr: *const c_char = get_mib(mib, name)?;
     let cstr = CStr::from_ptr(ptr);
-    cstr.to_str()
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    cstr.to_str().map_err(|error| Error::Utf8 {
+        name: name(),
+        error,
+    })
 }
 
-unsafe fn get_str(name: *const c_char) -> io::Result<&'static str> {
+unsafe fn get_str(name: &[u8]) -> Result<&'static str> {
     let ptr: *const c_char = get(name)?;
     let cstr = CStr::from_ptr(ptr);
-    cstr.to_str()
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    cstr.to_str().map_err(|error| Error::Utf8 {
+        name: name_string(name),
+        error,
+    })
 }
 
-unsafe fn set_mib<T>(mib: &[usize], mut value: T) -> io::Result<()> {
-    cvt(jemalloc_sys::mallctlbymib(
-        mib.as_ptr(),
-        mib.len(),
-        ptr::null_mut(),
-        ptr::null_mut(),
-        &mut value as *mut _ as *mut _,
-        mem::size_of::<T>(),
-    ))
+unsafe fn set_mib<T>(mib: &[usize], mut value: T, name: &dyn Fn() -> String) -> Result<()> {
+    cvt(
+        jemalloc_sys::mallctlbymib(
+            mib.as_ptr(),
+            mib.len(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut value as *mut _ as *mut _,
+            mem::size_of::<T>(),
+        ),
+        name,
+    )
 }
 
-unsafe fn set<T>(name: *const c_char, mut value: T) -> io::Result<()> {
-    cvt(jemalloc_sys::mallctl(
-        name,
-        ptr::null_mut(),
-        ptr::null_mut(),
-        &mut value as *mut _ as *mut _,
-        mem::size_of::<T>(),
-    ))
+unsafe fn set<T>(name: &[u8], mut value: T) -> Result<()> {
+    cvt(
+        jemalloc_sys::mallctl(
+            name_ptr(name)?,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut value as *mut _ as *mut _,
+            mem::size_of::<T>(),
+        ),
+        &|| name_string(name),
+    )
 }
 
-unsafe fn get_set_mib<T>(mib: &[usize], mut value: T) -> io::Result<T> {
+// Converts a string argument to a C string, reporting an `InvalidValue` error for the named
+// operation if it cannot be represented as one.
+fn c_str<'a, S>(value: &'a S, name: &dyn Fn() -> String) -> Result<Cow<'a, CStr>>
+where
+    S: ToCStr + ?Sized,
+{
+    value
+        .to_c_str()
+        .ok_or_else(|| Error::InvalidValue { name: name() })
+}
+
+unsafe fn set_str_mib<S>(mib: &[usize], value: &S, name: &dyn Fn() -> String) -> Result<()>
+where
+    S: ToCStr + ?Sized,
+{
+    let value = c_str(value, name)?;
+    set_mib(mib, value.as_ptr(), name)
+}
+
+unsafe fn set_str<S>(name: &[u8], value: &S) -> Result<()>
+where
+    S: ToCStr + ?Sized,
+{
+    let value = c_str(value, &|| name_string(name))?;
+    set(name, value.as_ptr())
+}
+
+unsafe fn get_set_mib<T, U>(mib: &[usize], mut value: U, name: &dyn Fn() -> String) -> Result<T> {
+    let mut old = MaybeUninit::<T>::uninit();
     let mut len = mem::size_of::<T>();
-    cvt(jemalloc_sys::mallctlbymib(
-        mib.as_ptr(),
-        mib.len(),
-        &mut value as *mut _ as *mut _,
-        &mut len,
-        &mut value as *mut _ as *mut _,
-        len,
-    ))?;
-    debug_assert_eq!(len, mem::size_of::<T>());
-    Ok(value)
+    cvt(
+        jemalloc_sys::mallctlbymib(
+            mib.as_ptr(),
+            mib.len(),
+            old.as_mut_ptr() as *mut _,
+            &mut len,
+            &mut value as *mut _ as *mut _,
+            mem::size_of::<U>(),
+        ),
+        name,
+    )?;
+    check_len::<T>(len, name)?;
+    Ok(old.assume_init())
 }
 
-unsafe fn get_set<T>(name: *const c_char, mut value: T) -> io::Result<T> {
+unsafe fn get_set<T, U>(name: &[u8], mut value: U) -> Result<T> {
+    let mut old = MaybeUninit::<T>::uninit();
     let mut len = mem::size_of::<T>();
-    cvt(jemalloc_sys::mallctl(
+    cvt(
+        jemalloc_sys::mallctl(
+            name_ptr(name)?,
+            old.as_mut_ptr() as *mut _,
+            &mut len,
+            &mut value as *mut _ as *mut _,
+            mem::size_of::<U>(),
+        ),
+        &|| name_string(name),
+    )?;
+    check_len::<T>(len, &|| name_string(name))?;
+    Ok(old.assume_init())
+}
+
+unsafe fn run_mib(mib: &[usize], name: &dyn Fn() -> String) -> Result<()> {
+    cvt(
+        jemalloc_sys::mallctlbymib(
+            mib.as_ptr(),
+            mib.len(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            0,
+        ),
         name,
-        &mut value as *mut _ as *mut _,
-        &mut len,
-        &mut value as *mut _ as *mut _,
-        len,
-    ))?;
-    debug_assert_eq!(len, mem::size_of::<T>());
-    Ok(value)
+    )
+}
+
+unsafe fn run(name: &[u8]) -> Result<()> {
+    cvt(
+        jemalloc_sys::mallctl(
+            name_ptr(name)?,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            ptr::null_mut(),
+            0,
+        ),
+        &|| name_string(name),
+    )
+}
+
+fn check_len<T>(len: usize, name: &dyn Fn() -> String) -> Result<()> {
+    if len == mem::size_of::<T>() {
+        Ok(())
+    } else {
+        Err(Error::SizeMismatch {
+            name: name(),
+            expected: mem::size_of::<T>(),
+            actual: len,
+        })
+    }
+}
+
+// Converts an `UnknownName` error into `None`, for operations which only exist in some jemalloc
+// versions.
+fn optional<T>(r: Result<T>) -> Result<Option<T>> {
+    match r {
+        Ok(v) => Ok(Some(v)),
+        Err(Error::UnknownName { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
 }
 
-fn cvt(ret: c_int) -> io::Result<()> {
+// Converts an `UnknownName` or `NotConfigured` error into `None`, for operations which may also be
+// compiled out of this jemalloc build.
+fn available<T>(r: Result<T>) -> Result<Option<T>> {
+    match optional(r) {
+        Err(Error::NotConfigured { .. }) => Ok(None),
+        r => r,
+    }
+}
+
+fn cvt(ret: c_int, name: &dyn Fn() -> String) -> Result<()> {
     if ret == 0 {
         Ok(())
     } else {
-        Err(io::Error::from_raw_os_error(ret as i32))
+        Err(Error::from_code(ret, name()))
     }
 }
 
-const VERSION: *const c_char = b"version\0" as *const _ as *const _;
+const VERSION: &[u8] = b"version\0";
 
 /// Returns the jemalloc version string.
 ///
@@ -225,7 +374,7 @@ const VERSION: *const c_char = b"version\0" as *const _ as *const _;
 ///     println!("jemalloc version {}", jemalloc_ctl::version().unwrap());
 /// }
 /// ```
-pub fn version() -> io::Result<&'static str> {
+pub fn version() -> Result<&'static str> {
     unsafe { get_str(VERSION) }
 }
 
@@ -248,25 +397,21 @@ pub fn version() -> io::Result<&'static str> {
 ///     println!("jemalloc version {}", version.get().unwrap());
 /// }
 #[derive(Copy, Clone)]
-pub struct Version([usize; 1]);
+pub struct Version(MibStr<[usize; 1]>);
 
 impl Version {
     /// Returns a new `Version`.
-    pub fn new() -> io::Result<Version> {
-        let mut mib = [0; 1];
-        unsafe {
-            name_to_mib(VERSION, &mut mib)?;
-        }
-        Ok(Version(mib))
+    pub fn new() -> Result<Version> {
+        unsafe { MibStr::new(VERSION).map(Version) }
     }
 
     /// Returns the jemalloc version string.
-    pub fn get(&self) -> io::Result<&'static str> {
-        unsafe { get_str_mib(&self.0) }
+    pub fn get(&self) -> Result<&'static str> {
+        self.0.read()
     }
 }
 
-const EPOCH: *const c_char = b"epoch\0" as *const _ as *const _;
+const EPOCH: &[u8] = b"epoch\0";
 
 /// Advances the jemalloc epoch, returning it.
 ///
@@ -290,8 +435,8 @@ const EPOCH: *const c_char = b"epoch\0" as *const _ as *const _;
 ///     assert_eq!(a + 1, b);
 /// }
 /// ```
-pub fn epoch() -> io::Result<u64> {
-    unsafe { get_set(EPOCH, 1) }
+pub fn epoch() -> Result<u64> {
+    unsafe { get_set(EPOCH, 1u64) }
 }
 
 /// A type providing access to the jemalloc epoch.
@@ -320,28 +465,24 @@ pub fn epoch() -> io::Result<u64> {
 ///     assert_eq!(a + 1, b);
 /// }
 #[derive(Copy, Clone)]
-pub struct Epoch([usize; 1]);
+pub struct Epoch(Mib<u64, [usize; 1]>);
 
 impl Epoch {
     /// Returns a new `Epoch`.
-    pub fn new() -> io::Result<Epoch> {
-        let mut mib = [0; 1];
-        unsafe {
-            name_to_mib(EPOCH, &mut mib)?;
-        }
-        Ok(Epoch(mib))
+    pub fn new() -> Result<Epoch> {
+        unsafe { Mib::new(EPOCH).map(Epoch) }
     }
 
     /// Advances the epoch, returning it.
     ///
     /// The epoch advances by 1 every time it is advanced, so the value can be used to determine if
     /// another thread triggered a referesh.
-    pub fn advance(&self) -> io::Result<u64> {
-        unsafe { get_set_mib(&self.0, 1) }
+    pub fn advance(&self) -> Result<u64> {
+        self.0.update(1)
     }
 }
 
-const BACKGROUND_THREAD: *const c_char = b"background_thread\0" as *const _ as *const _;
+const BACKGROUND_THREAD: &[u8] = b"background_thread\0";
 
 /// Returns the state of internal background worker threads.
 ///
@@ -360,7 +501,7 @@ const BACKGROUND_THREAD: *const c_char = b"background_thread\0" as *const _ as *
 ///     println!("background_thread: {}", jemalloc_ctl::background_thread().unwrap());
 /// }
 /// ```
-pub fn background_thread() -> io::Result<bool> {
+pub fn background_thread() -> Result<bool> {
     unsafe { get(BACKGROUND_THREAD) }
 }
 
@@ -382,7 +523,7 @@ pub fn background_thread() -> io::Result<bool> {
 ///     assert!(jemalloc_ctl::background_thread().unwrap());
 /// }
 /// ```
-pub fn set_background_thread(background_thread: bool) -> io::Result<()> {
+pub fn set_background_thread(background_thread: bool) -> Result<()> {
     unsafe { set(BACKGROUND_THREAD, background_thread) }
 }
 
@@ -406,30 +547,26 @@ pub fn set_background_thread(background_thread: bool) -> io::Result<()> {
 /// }
 /// ```
 #[derive(Copy, Clone)]
-pub struct BackgroundThread([usize; 1]);
+pub struct BackgroundThread(Mib<bool, [usize; 1]>);
 
 impl BackgroundThread {
     /// Returns a new `BackgroundThread`.
-    pub fn new() -> io::Result<BackgroundThread> {
-        let mut mib = [0; 1];
-        unsafe {
-            name_to_mib(BACKGROUND_THREAD, &mut mib)?;
-        }
-        Ok(BackgroundThread(mib))
+    pub fn new() -> Result<BackgroundThread> {
+        unsafe { Mib::new(BACKGROUND_THREAD).map(BackgroundThread) }
     }
 
     /// Returns the current background thread state.
-    pub fn get(&self) -> io::Result<bool> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
     }
 
     /// Sets the background thread state.
-    pub fn set(&self, background_thread: bool) -> io::Result<()> {
-        unsafe { set_mib(&self.0, background_thread) }
+    pub fn set(&self, background_thread: bool) -> Result<()> {
+        self.0.write(background_thread)
     }
 }
 
-const MAX_BACKGROUND_THREADS: *const c_char = b"max_background_threads\0" as *const _ as *const _;
+const MAX_BACKGROUND_THREADS: &[u8] = b"max_background_threads\0";
 
 /// Returns the maximum number of background threads that will be created.
 ///
@@ -444,7 +581,7 @@ const MAX_BACKGROUND_THREADS: *const c_char = b"max_background_threads\0" as *co
 ///     println!("max_background_threads: {}", jemalloc_ctl::max_background_threads().unwrap());
 /// }
 /// ```
-pub fn max_background_threads() -> io::Result<usize> {
+pub fn max_background_threads() -> Result<usize> {
     unsafe { get(MAX_BACKGROUND_THREADS) }
 }
 
@@ -462,7 +599,7 @@ pub fn max_background_threads() -> io::Result<usize> {
 ///     assert_eq!(jemalloc_ctl::max_background_threads().unwrap(), 1);
 /// }
 /// ```
-pub fn set_max_background_threads(max_background_threads: usize) -> io::Result<()> {
+pub fn set_max_background_threads(max_background_threads: usize) -> Result<()> {
     unsafe { set(MAX_BACKGROUND_THREADS, max_background_threads) }
 }
 
@@ -482,25 +619,106 @@ pub fn set_max_background_threads(max_background_threads: usize) -> io::Result<(
 /// }
 /// ```
 #[derive(Copy, Clone)]
-pub struct MaxBackgroundThreads([usize; 1]);
+pub struct MaxBackgroundThreads(Mib<usize, [usize; 1]>);
 
 impl MaxBackgroundThreads {
     /// Returns a new `MaxBackgroundThreads`.
-    pub fn new() -> io::Result<MaxBackgroundThreads> {
-        let mut mib = [0; 1];
-        unsafe {
-            name_to_mib(MAX_BACKGROUND_THREADS, &mut mib)?;
-        }
-        Ok(MaxBackgroundThreads(mib))
+    pub fn new() -> Result<MaxBackgroundThreads> {
+        unsafe { Mib::new(MAX_BACKGROUND_THREADS).map(MaxBackgroundThreads) }
     }
 
     /// Returns the current background thread limit.
-    pub fn get(&self) -> io::Result<usize> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
     }
 
     /// Sets the background thread limit.
-    pub fn set(&self, max_background_threads: usize) -> io::Result<()> {
-        unsafe { set_mib(&self.0, max_background_threads) }
+    pub fn set(&self, max_background_threads: usize) -> Result<()> {
+        self.0.write(max_background_threads)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fmt;
+    use std::io;
+
+    fn assert_invalid_value<T>(r: Result<T>, expected: &str)
+    where
+        T: fmt::Debug,
+    {
+        match r {
+            Err(Error::InvalidValue { ref name }) => assert_eq!(name, expected),
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn read_wrong_size() {
+        unsafe {
+            assert_invalid_value(get::<u64>(b"opt.abort\0"), "opt.abort");
+            assert_invalid_value(get::<bool>(b"epoch\0"), "epoch");
+        }
+    }
+
+    #[test]
+    fn read_mib_wrong_size() {
+        let abort = unsafe { Mib::<u64, [usize; 2]>::new(b"opt.abort\0").unwrap() };
+        assert_invalid_value(abort.read(), "opt.abort");
+
+        let epoch = unsafe { Mib::<bool, [usize; 1]>::new(b"epoch\0").unwrap() };
+        assert_invalid_value(epoch.read(), "epoch");
+        assert_invalid_value(epoch.update(true), "epoch");
+    }
+
+    #[test]
+    fn update_wrong_size() {
+        unsafe {
+            assert_invalid_value(get_set::<u8, u8>(b"epoch\0", 1), "epoch");
+        }
+    }
+
+    #[test]
+    fn check_len_mismatch() {
+        match check_len::<u64>(1, &|| "opt.abort".to_string()) {
+            Err(Error::SizeMismatch {
+                ref name,
+                expected: 8,
+                actual: 1,
+            }) if name == "opt.abort" => {}
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn write_str_interior_nul() {
+        match unsafe { set_str(b"arena.0.dss\0", "primary\0") } {
+            Err(Error::InvalidValue { ref name }) => assert_eq!(name, "arena.0.dss"),
+            r => panic!("unexpected result {:?}", r),
+        }
+
+        let dss = unsafe { MibStr::<[usize; 3]>::new(b"arena.0.dss\0").unwrap() };
+        match dss.with_index(0, 1).write("second\0ary") {
+            Err(Error::InvalidValue { ref name }) => assert_eq!(name, "arena.1.dss"),
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn busy_is_not_out_of_memory() {
+        match Error::from_code(libc::EAGAIN, "arenas.create".to_string()) {
+            Error::Busy { ref name } => assert_eq!(name, "arenas.create"),
+            e => panic!("unexpected error {:?}", e),
+        }
+        match Error::from_code(libc::ENOMEM, "arenas.create".to_string()) {
+            Error::OutOfMemory { .. } => {}
+            e => panic!("unexpected error {:?}", e),
+        }
+        let e: io::Error = Error::Busy {
+            name: "background_thread".to_string(),
+        }
+        .into();
+        assert_eq!(e.kind(), io::ErrorKind::WouldBlock);
     }
 }
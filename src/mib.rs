@@ -0,0 +1,362 @@
+//! Generic MIB-based access to `mallctl` operations.
+use std::marker::PhantomData;
+
+use {
+    get_mib, get_set_mib, get_str_mib, name_string, name_to_mib, run_mib, set_mib, set_str_mib,
+    Result, ToCStr,
+};
+
+/// The length of a MIB.
+///
+/// A MIB has one component for each period-separated segment of the name it was translated from,
+/// so `stats.allocated` has a length of 2 and `stats.arenas.0.pactive` has a length of 4. This
+/// trait is implemented for `[usize; N]` for `N` from 1 through 8, which is enough for every name
+/// jemalloc currently exposes.
+pub trait MibLen: private::Sealed + Copy + Default + AsRef<[usize]> + AsMut<[usize]> {}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! mib_len {
+    ($($n:expr)*) => {
+        $(
+            impl private::Sealed for [usize; $n] {}
+            impl MibLen for [usize; $n] {}
+        )*
+    }
+}
+
+mib_len!(1 2 3 4 5 6 7 8);
+
+// Returns a bitmask of the components of the name which are numeric indices.
+fn index_mask(name: &[u8]) -> u8 {
+    let name = match name.split_last() {
+        Some((&0, name)) => name,
+        _ => name,
+    };
+    name.split(|&b| b == b'.')
+        .take(8)
+        .enumerate()
+        .filter(|&(_, part)| !part.is_empty() && part.iter().all(u8::is_ascii_digit))
+        .fold(0, |mask, (i, _)| mask | 1 << i)
+}
+
+// Returns the name with its numeric components replaced by the current values in the MIB.
+fn render_name(name: &[u8], mib: &[usize], mask: u8) -> String {
+    let name = name_string(name);
+    if mask == 0 {
+        return name;
+    }
+    name.split('.')
+        .enumerate()
+        .map(|(i, part)| match mib.get(i) {
+            Some(index) if mask & (1 << i) != 0 => index.to_string(),
+            _ => part.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn substitute_index(mib: &mut [usize], mask: u8, n: usize, index: usize) {
+    let position = (0..mib.len())
+        .filter(|&i| mask & (1 << i) != 0)
+        .nth(n)
+        .unwrap_or_else(|| panic!("MIB has no index component {}", n));
+    mib[position] = index;
+}
+
+/// A typed handle to a `mallctl` operation.
+///
+/// This is the building block used by the operation-specific types in this crate, and can be used
+/// directly to access operations the crate doesn't provide a wrapper for. `T` is the type of the
+/// operation's value, and `L` is the length of its MIB (e.g. `[usize; 2]` for `stats.allocated`).
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Mib;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch: Mib<u64, [usize; 1]> = unsafe { Mib::new(b"epoch\0").unwrap() };
+///     let allocated: Mib<usize, [usize; 2]> = unsafe { Mib::new(b"stats.allocated\0").unwrap() };
+///
+///     let a = allocated.read().unwrap();
+///     let _buf = vec![0; 1024 * 1024];
+///     epoch.update(1).unwrap();
+///     let b = allocated.read().unwrap();
+///     assert!(a < b);
+/// }
+/// ```
+///
+/// Names with numeric components, like `stats.arenas.<i>.pactive`, can be translated once and then
+/// reused for other indices:
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Mib;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let narenas = jemalloc_ctl::arenas::narenas().unwrap();
+///     let pactive: Mib<usize, [usize; 4]> =
+///         unsafe { Mib::new(b"stats.arenas.0.pactive\0").unwrap() };
+///
+///     for arena in 0..narenas as usize {
+///         // arenas which have not been initialized yet have no statistics
+///         if let Ok(pages) = pactive.with_index(0, arena).read() {
+///             println!("arena {}: {} active pages", arena, pages);
+///         }
+///     }
+/// }
+/// ```
+pub struct Mib<T, L> {
+    mib: L,
+    name: &'static [u8],
+    indices: u8,
+    _p: PhantomData<fn() -> T>,
+}
+
+impl<T, L> Copy for Mib<T, L> where L: MibLen {}
+
+impl<T, L> Clone for Mib<T, L>
+where
+    L: MibLen,
+{
+    fn clone(&self) -> Mib<T, L> {
+        *self
+    }
+}
+
+impl<T, L> Mib<T, L>
+where
+    L: MibLen,
+{
+    /// Returns a new `Mib` for the operation with the specified name.
+    ///
+    /// The name must be NUL-terminated, and must translate to a MIB of exactly the length of `L`.
+    ///
+    /// The name is required to be `'static` so that `Mib` can be `Copy`, and can report the name of
+    /// the operation in errors without allocating. Names which only differ in their numeric
+    /// components can share a `Mib` through [`with_index`]. Other names built at run time can be
+    /// used with the functions in the [`raw`] module, or leaked with `Box::leak`.
+    ///
+    /// # Safety
+    ///
+    /// `T` must be the type jemalloc uses for the operation's value.
+    ///
+    /// [`with_index`]: #method.with_index
+    /// [`raw`]: raw/index.html
+    pub unsafe fn new(name: &'static [u8]) -> Result<Mib<T, L>> {
+        let mut mib = L::default();
+        name_to_mib(name, mib.as_mut())?;
+        Ok(Mib {
+            mib,
+            name,
+            indices: index_mask(name),
+            _p: PhantomData,
+        })
+    }
+
+    /// Returns the raw MIB.
+    pub fn as_slice(&self) -> &[usize] {
+        self.mib.as_ref()
+    }
+
+    /// Returns a copy of this `Mib` with its `n`th numeric component replaced by `index`.
+    ///
+    /// Only components which were numeric in the name passed to `new` count, so for a `Mib` of
+    /// `stats.arenas.0.bins.3.nmalloc`, `n` is 0 for the arena index and 1 for the bin index. No
+    /// lookup is performed; an invalid index is reported when the `Mib` is used.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name has fewer than `n + 1` numeric components.
+    pub fn with_index(&self, n: usize, index: usize) -> Mib<T, L> {
+        let mut mib = *self;
+        substitute_index(mib.mib.as_mut(), self.indices, n, index);
+        mib
+    }
+
+    fn name(&self) -> String {
+        render_name(self.name, self.mib.as_ref(), self.indices)
+    }
+}
+
+impl<T, L> Mib<T, L>
+where
+    T: Copy,
+    L: MibLen,
+{
+    /// Returns the current value of the operation.
+    ///
+    /// If `T` is not the same size as the operation's value, jemalloc usually rejects the read,
+    /// which is reported as [`Error::InvalidValue`]. [`Error::SizeMismatch`] is only returned if
+    /// jemalloc instead writes a shorter value than requested.
+    ///
+    /// [`Error::InvalidValue`]: enum.Error.html#variant.InvalidValue
+    /// [`Error::SizeMismatch`]: enum.Error.html#variant.SizeMismatch
+    pub fn read(&self) -> Result<T> {
+        unsafe { get_mib(self.mib.as_ref(), &|| self.name()) }
+    }
+
+    /// Sets the value of the operation.
+    pub fn write(&self, value: T) -> Result<()> {
+        unsafe { set_mib(self.mib.as_ref(), value, &|| self.name()) }
+    }
+
+    /// Sets the value of the operation, returning its previous value.
+    pub fn update(&self, value: T) -> Result<T> {
+        unsafe { get_set_mib(self.mib.as_ref(), value, &|| self.name()) }
+    }
+}
+
+impl<L> Mib<(), L>
+where
+    L: MibLen,
+{
+    /// Performs the operation.
+    ///
+    /// This is used for operations which neither read nor write a value, such as
+    /// `stats.mutexes.reset`.
+    pub fn run(&self) -> Result<()> {
+        unsafe { run_mib(self.mib.as_ref(), &|| self.name()) }
+    }
+}
+
+/// A handle to a string-valued `mallctl` operation.
+///
+/// jemalloc represents these values as `const char *`, which `MibStr` converts to a `&str` when
+/// reading, and from any [`ToCStr`] value when writing.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::MibStr;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let dss: MibStr<[usize; 2]> = unsafe { MibStr::new(b"opt.dss\0").unwrap() };
+///
+///     println!("dss priority: {}", dss.read().unwrap());
+/// }
+/// ```
+///
+/// [`ToCStr`]: trait.ToCStr.html
+pub struct MibStr<L> {
+    mib: L,
+    name: &'static [u8],
+    indices: u8,
+}
+
+impl<L> Copy for MibStr<L> where L: MibLen {}
+
+impl<L> Clone for MibStr<L>
+where
+    L: MibLen,
+{
+    fn clone(&self) -> MibStr<L> {
+        *self
+    }
+}
+
+impl<L> MibStr<L>
+where
+    L: MibLen,
+{
+    /// Returns a new `MibStr` for the operation with the specified name.
+    ///
+    /// The name must be NUL-terminated, and must translate to a MIB of exactly the length of `L`.
+    /// As with [`Mib::new`], the name is required to be `'static`.
+    ///
+    /// # Safety
+    ///
+    /// The operation's value must be a `const char *`. If the operation is read, the string it
+    /// points to must live for the remainder of the program.
+    ///
+    /// [`Mib::new`]: struct.Mib.html#method.new
+    pub unsafe fn new(name: &'static [u8]) -> Result<MibStr<L>> {
+        let mut mib = L::default();
+        name_to_mib(name, mib.as_mut())?;
+        Ok(MibStr {
+            mib,
+            name,
+            indices: index_mask(name),
+        })
+    }
+
+    /// Returns the raw MIB.
+    pub fn as_slice(&self) -> &[usize] {
+        self.mib.as_ref()
+    }
+
+    /// Returns a copy of this `MibStr` with its `n`th numeric component replaced by `index`.
+    ///
+    /// See [`Mib::with_index`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name has fewer than `n + 1` numeric components.
+    ///
+    /// [`Mib::with_index`]: struct.Mib.html#method.with_index
+    pub fn with_index(&self, n: usize, index: usize) -> MibStr<L> {
+        let mut mib = *self;
+        substitute_index(mib.mib.as_mut(), self.indices, n, index);
+        mib
+    }
+
+    fn name(&self) -> String {
+        render_name(self.name, self.mib.as_ref(), self.indices)
+    }
+
+    /// Returns the current value of the operation.
+    pub fn read(&self) -> Result<&'static str> {
+        unsafe { get_str_mib(self.mib.as_ref(), &|| self.name()) }
+    }
+
+    /// Sets the value of the operation.
+    ///
+    /// An [`Error::InvalidValue`] error is returned if the value contains an interior NUL byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate jemallocator;
+    /// extern crate jemalloc_ctl;
+    ///
+    /// use jemalloc_ctl::MibStr;
+    ///
+    /// #[global_allocator]
+    /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+    ///
+    /// fn main() {
+    ///     let dss: MibStr<[usize; 3]> = unsafe { MibStr::new(b"arena.0.dss\0").unwrap() };
+    ///
+    ///     dss.write("secondary").unwrap();
+    ///     assert_eq!(dss.read().unwrap(), "secondary");
+    /// }
+    /// ```
+    ///
+    /// [`Error::InvalidValue`]: enum.Error.html#variant.InvalidValue
+    pub fn write<S>(&self, value: &S) -> Result<()>
+    where
+        S: ToCStr + ?Sized,
+    {
+        unsafe { set_str_mib(self.mib.as_ref(), value, &|| self.name()) }
+    }
+}
@@ -1,12 +1,90 @@
 //! Information about the run-time jemalloc configuration.
 //!
 //! These settings are controlled by the `MALLOC_CONF` environment variable.
-use std::io;
-use std::os::raw::{c_char, c_uint};
+use libc::ssize_t;
+use std::error;
+use std::fmt;
+use std::os::raw::c_uint;
+use std::str::FromStr;
 
-use {get, get_mib, get_str, get_str_mib, name_to_mib};
+use arenas::DecayTime;
+use {get, get_str, name_string, Error, Mib, MibStr, Result};
 
-const ABORT: *const c_char = b"opt.abort\0" as *const _ as *const _;
+macro_rules! modes {
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$vattr:meta])*
+                $variant:ident => $s:tt,
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                $(#[$vattr])*
+                $variant,
+            )*
+        }
+
+        impl $name {
+            /// Returns the name jemalloc uses for the mode.
+            pub fn as_str(&self) -> &'static str {
+                match *self {
+                    $($name::$variant => $s,)*
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseModeError;
+
+            fn from_str(s: &str) -> ::std::result::Result<$name, ParseModeError> {
+                match s {
+                    $($s => Ok($name::$variant),)*
+                    _ => Err(ParseModeError(())),
+                }
+            }
+        }
+    }
+}
+
+/// An error returned when parsing a mode from a string fails.
+///
+/// Modes are parsed from the names jemalloc uses for them, such as "secondary" for
+/// [`DssMode::Secondary`].
+///
+/// [`DssMode::Secondary`]: enum.DssMode.html#variant.Secondary
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseModeError(());
+
+impl fmt::Display for ParseModeError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("unrecognized mode")
+    }
+}
+
+impl error::Error for ParseModeError {}
+
+fn parse<T>(name: &[u8], value: &str) -> Result<T>
+where
+    T: FromStr,
+{
+    value.parse().map_err(|_| Error::UnexpectedValue {
+        name: name_string(name),
+        value: value.to_string(),
+    })
+}
+
+const ABORT: &[u8] = b"opt.abort\0";
 
 /// Determines if jemalloc will call `abort(3)` on most warnings.
 ///
@@ -25,7 +103,7 @@ const ABORT: *const c_char = b"opt.abort\0" as *const _ as *const _;
 ///     println!("abort on warning: {}", jemalloc_ctl::opt::abort().unwrap());
 /// }
 /// ```
-pub fn abort() -> io::Result<bool> {
+pub fn abort() -> Result<bool> {
     unsafe { get(ABORT) }
 }
 
@@ -50,31 +128,225 @@ pub fn abort() -> io::Result<bool> {
 ///     println!("abort on warning: {}", abort.get().unwrap());
 /// }
 /// ```
-pub struct Abort([usize; 2]);
+pub struct Abort(Mib<bool, [usize; 2]>);
 
 impl Abort {
     /// Returns a new `Abort`.
-    pub fn new() -> io::Result<Abort> {
-        unsafe {
-            let mut mib = [0; 2];
-            name_to_mib(ABORT, &mut mib)?;
-            Ok(Abort(mib))
-        }
+    pub fn new() -> Result<Abort> {
+        unsafe { Mib::new(ABORT).map(Abort) }
     }
 
     /// Returns the abort-on-warning behavior.
-    pub fn get(&self) -> io::Result<bool> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const ABORT_CONF: &[u8] = b"opt.abort_conf\0";
+
+/// Determines if jemalloc will call `abort(3)` if invalid configuration options are found.
+///
+/// Invalid options are otherwise reported with a warning and ignored. This is disabled by default
+/// unless `--enable-debug` was specified during build configuration.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("abort on invalid configuration: {}", jemalloc_ctl::opt::abort_conf().unwrap());
+/// }
+/// ```
+pub fn abort_conf() -> Result<bool> {
+    unsafe { get(ABORT_CONF) }
+}
+
+/// A type determining if jemalloc will call `abort(3)` if invalid configuration options are found.
+///
+/// Invalid options are otherwise reported with a warning and ignored. This is disabled by default
+/// unless `--enable-debug` was specified during build configuration.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::AbortConf;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let abort_conf = AbortConf::new().unwrap();
+///
+///     println!("abort on invalid configuration: {}", abort_conf.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct AbortConf(Mib<bool, [usize; 2]>);
+
+impl AbortConf {
+    /// Returns a new `AbortConf`.
+    pub fn new() -> Result<AbortConf> {
+        unsafe { Mib::new(ABORT_CONF).map(AbortConf) }
+    }
+
+    /// Returns the abort-on-invalid-configuration behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const METADATA_THP: &[u8] = b"opt.metadata_thp\0";
+
+/// Returns the transparent huge page (THP) mode used for jemalloc's internal metadata.
+///
+/// With `Auto`, huge pages are used once metadata usage reaches a threshold, and with `Always` they
+/// are used from the start. The default is `Disabled`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("metadata THP mode: {}", jemalloc_ctl::opt::metadata_thp().unwrap());
+/// }
+/// ```
+pub fn metadata_thp() -> Result<MetadataThpMode> {
+    unsafe { get_str(METADATA_THP).and_then(|mode| parse(METADATA_THP, mode)) }
+}
+
+/// A type providing access to the transparent huge page (THP) mode used for jemalloc's internal
+/// metadata.
+///
+/// With `Auto`, huge pages are used once metadata usage reaches a threshold, and with `Always` they
+/// are used from the start. The default is `Disabled`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::MetadataThp;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let metadata_thp = MetadataThp::new().unwrap();
+///
+///     println!("metadata THP mode: {}", metadata_thp.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct MetadataThp(MibStr<[usize; 2]>);
+
+impl MetadataThp {
+    /// Returns a new `MetadataThp`.
+    pub fn new() -> Result<MetadataThp> {
+        unsafe { MibStr::new(METADATA_THP).map(MetadataThp) }
+    }
+
+    /// Returns the metadata THP mode.
+    pub fn get(&self) -> Result<MetadataThpMode> {
+        self.0.read().and_then(|mode| parse(METADATA_THP, mode))
+    }
+}
+
+modes! {
+    /// The transparent huge page mode used for jemalloc's internal metadata.
+    pub enum MetadataThpMode {
+        /// Huge pages are not used for metadata.
+        Disabled => "disabled",
+        /// Huge pages are used for metadata once metadata usage reaches a threshold.
+        Auto => "auto",
+        /// Huge pages are always used for metadata.
+        Always => "always",
+    }
+}
+
+const RETAIN: &[u8] = b"opt.retain\0";
+
+/// Determines if unused virtual memory is retained for later reuse rather than unmapped.
+///
+/// Retained memory is discarded with `madvise(2)` or similar, so it does not count towards resident
+/// memory, but remains mapped. This is enabled by default on 64-bit Linux, where it reduces virtual
+/// memory map fragmentation.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("retain unused virtual memory: {}", jemalloc_ctl::opt::retain().unwrap());
+/// }
+/// ```
+pub fn retain() -> Result<bool> {
+    unsafe { get(RETAIN) }
+}
+
+/// A type determining if unused virtual memory is retained for later reuse rather than unmapped.
+///
+/// Retained memory is discarded with `madvise(2)` or similar, so it does not count towards resident
+/// memory, but remains mapped. This is enabled by default on 64-bit Linux, where it reduces virtual
+/// memory map fragmentation.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::Retain;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let retain = Retain::new().unwrap();
+///
+///     println!("retain unused virtual memory: {}", retain.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Retain(Mib<bool, [usize; 2]>);
+
+impl Retain {
+    /// Returns a new `Retain`.
+    pub fn new() -> Result<Retain> {
+        unsafe { Mib::new(RETAIN).map(Retain) }
+    }
+
+    /// Returns the virtual memory retention behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
     }
 }
 
-const DSS: *const c_char = b"opt.dss\0" as *const _ as *const _;
+const DSS: &[u8] = b"opt.dss\0";
 
 /// Returns the dss (`sbrk(2)`) allocation precedence as related to `mmap(2)` allocation.
 ///
-/// The following settings are supported if `sbrk(2)` is supported by the operating system:
-/// "disabled", "primary", and "secondary"; otherwise only "disabled" is supported. The default is
-/// "secondary" if `sbrk(2)` is supported by the operating system; "disabled" otherwise.
+/// All modes are supported if `sbrk(2)` is supported by the operating system; otherwise only
+/// `Disabled` is supported. The default is `Secondary` if `sbrk(2)` is supported by the operating
+/// system; `Disabled` otherwise.
 ///
 /// # Examples
 ///
@@ -89,16 +361,16 @@ const DSS: *const c_char = b"opt.dss\0" as *const _ as *const _;
 ///     println!("dss priority: {}", jemalloc_ctl::opt::dss().unwrap());
 /// }
 /// ```
-pub fn dss() -> io::Result<&'static str> {
-    unsafe { get_str(DSS) }
+pub fn dss() -> Result<DssMode> {
+    unsafe { get_str(DSS).and_then(|mode| parse(DSS, mode)) }
 }
 
 /// A type providing access to the dss (`sbrk(2)`) allocation precedence as related to `mmap(2)`
 /// allocation.
 ///
-/// The following settings are supported if `sbrk(2)` is supported by the operating system:
-/// "disabled", "primary", and "secondary"; otherwise only "disabled" is supported. The default is
-/// "secondary" if `sbrk(2)` is supported by the operating system; "disabled" otherwise.
+/// All modes are supported if `sbrk(2)` is supported by the operating system; otherwise only
+/// `Disabled` is supported. The default is `Secondary` if `sbrk(2)` is supported by the operating
+/// system; `Disabled` otherwise.
 ///
 /// # Examples
 ///
@@ -118,25 +390,33 @@ pub fn dss() -> io::Result<&'static str> {
 /// }
 /// ```
 #[derive(Copy, Clone)]
-pub struct Dss([usize; 2]);
+pub struct Dss(MibStr<[usize; 2]>);
 
 impl Dss {
     /// Returns a new `Dss`.
-    pub fn new() -> io::Result<Dss> {
-        unsafe {
-            let mut mib = [0; 2];
-            name_to_mib(DSS, &mut mib)?;
-            Ok(Dss(mib))
-        }
+    pub fn new() -> Result<Dss> {
+        unsafe { MibStr::new(DSS).map(Dss) }
     }
 
     /// Returns the dss allocation precedence.
-    pub fn get(&self) -> io::Result<&'static str> {
-        unsafe { get_str_mib(&self.0) }
+    pub fn get(&self) -> Result<DssMode> {
+        self.0.read().and_then(|mode| parse(DSS, mode))
+    }
+}
+
+modes! {
+    /// The dss (`sbrk(2)`) allocation precedence as related to `mmap(2)` allocation.
+    pub enum DssMode {
+        /// `sbrk(2)` is not used.
+        Disabled => "disabled",
+        /// `sbrk(2)` is preferred over `mmap(2)`.
+        Primary => "primary",
+        /// `mmap(2)` is preferred over `sbrk(2)`.
+        Secondary => "secondary",
     }
 }
 
-const NARENAS: *const c_char = b"opt.narenas\0" as *const _ as *const _;
+const NARENAS: &[u8] = b"opt.narenas\0";
 
 /// Returns the maximum number of arenas to use for automatic multiplexing of threads and arenas.
 ///
@@ -155,7 +435,7 @@ const NARENAS: *const c_char = b"opt.narenas\0" as *const _ as *const _;
 ///     println!("number of arenas: {}", jemalloc_ctl::opt::narenas().unwrap());
 /// }
 /// ```
-pub fn narenas() -> io::Result<c_uint> {
+pub fn narenas() -> Result<c_uint> {
     unsafe { get(NARENAS) }
 }
 
@@ -182,37 +462,50 @@ pub fn narenas() -> io::Result<c_uint> {
 /// }
 /// ```
 #[derive(Copy, Clone)]
-pub struct NArenas([usize; 2]);
+pub struct NArenas(Mib<c_uint, [usize; 2]>);
 
 impl NArenas {
     /// Returns a new `NArenas`.
-    pub fn new() -> io::Result<NArenas> {
-        unsafe {
-            let mut mib = [0; 2];
-            name_to_mib(NARENAS, &mut mib)?;
-            Ok(NArenas(mib))
-        }
+    pub fn new() -> Result<NArenas> {
+        unsafe { Mib::new(NARENAS).map(NArenas) }
     }
 
     /// Returns the maximum number of arenas.
-    pub fn get(&self) -> io::Result<c_uint> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<c_uint> {
+        self.0.read()
     }
 }
 
-const JUNK: *const c_char = b"opt.junk\0" as *const _ as *const _;
+const PERCPU_ARENA: &[u8] = b"opt.percpu_arena\0";
 
-/// Returns jemalloc's junk filling mode.
+/// Returns the per-CPU arena mode.
 ///
-/// Requires `--enable-fill` to have been specified during build configuration.
+/// With `Percpu`, threads are assigned to an arena per CPU, and with `Phycpu` to an arena per
+/// physical CPU, based on the CPU the thread runs on. The default is `Disabled`, in which case
+/// threads are assigned to arenas round-robin.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 ///
-/// If set to "alloc", each byte of uninitialized allocated memory will be set to `0x5a`. If set to
-/// "free", each byte of deallocated memory will be set to `0x5a`. If set to "true", both allocated
-/// and deallocated memory will be initialized, and if set to "false" junk filling will be disabled.
-/// This is intended for debugging and will impact performance negatively.
+/// fn main() {
+///     println!("per-CPU arena mode: {}", jemalloc_ctl::opt::percpu_arena().unwrap());
+/// }
+/// ```
+pub fn percpu_arena() -> Result<PercpuArenaMode> {
+    unsafe { get_str(PERCPU_ARENA).and_then(|mode| parse(PERCPU_ARENA, mode)) }
+}
+
+/// A type providing access to the per-CPU arena mode.
 ///
-/// The default is "false", unless `--enable-debug` was specified during build configuration, in
-/// which case the default is "true".
+/// With `Percpu`, threads are assigned to an arena per CPU, and with `Phycpu` to an arena per
+/// physical CPU, based on the CPU the thread runs on. The default is `Disabled`, in which case
+/// threads are assigned to arenas round-robin.
 ///
 /// # Examples
 ///
@@ -220,28 +513,77 @@ const JUNK: *const c_char = b"opt.junk\0" as *const _ as *const _;
 /// extern crate jemallocator;
 /// extern crate jemalloc_ctl;
 ///
+/// use jemalloc_ctl::opt::PercpuArena;
+///
 /// #[global_allocator]
 /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 ///
 /// fn main() {
-///     println!("junk filling: {}", jemalloc_ctl::opt::junk().unwrap());
+///     let percpu_arena = PercpuArena::new().unwrap();
+///
+///     println!("per-CPU arena mode: {}", percpu_arena.get().unwrap());
 /// }
 /// ```
-pub fn junk() -> io::Result<&'static str> {
-    unsafe { get_str(JUNK) }
+#[derive(Copy, Clone)]
+pub struct PercpuArena(MibStr<[usize; 2]>);
+
+impl PercpuArena {
+    /// Returns a new `PercpuArena`.
+    pub fn new() -> Result<PercpuArena> {
+        unsafe { MibStr::new(PERCPU_ARENA).map(PercpuArena) }
+    }
+
+    /// Returns the per-CPU arena mode.
+    pub fn get(&self) -> Result<PercpuArenaMode> {
+        self.0.read().and_then(|mode| parse(PERCPU_ARENA, mode))
+    }
 }
 
-/// A type providing access to jemalloc's junk filling mode.
+modes! {
+    /// The per-CPU arena mode.
+    pub enum PercpuArenaMode {
+        /// Threads are assigned to arenas round-robin.
+        Disabled => "disabled",
+        /// Threads are assigned to an arena per CPU.
+        Percpu => "percpu",
+        /// Threads are assigned to an arena per physical CPU.
+        Phycpu => "phycpu",
+    }
+}
+
+const OVERSIZE_THRESHOLD: &[u8] = b"opt.oversize_threshold\0";
+
+/// Returns the size in bytes above which allocations are served from a dedicated arena.
 ///
-/// Requires `--enable-fill` to have been specified during build configuration.
+/// This option was added in jemalloc 5.2. An [`Error::UnknownName`] error is returned with earlier
+/// versions.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(oversize_threshold) = jemalloc_ctl::opt::oversize_threshold() {
+///         println!("oversize threshold: {}", oversize_threshold);
+///     }
+/// }
+/// ```
 ///
-/// If set to "alloc", each byte of uninitialized allocated memory will be set to `0x5a`. If set to
-/// "free", each byte of deallocated memory will be set to `0x5a`. If set to "true", both allocated
-/// and deallocated memory will be initialized, and if set to "false" junk filling will be disabled.
-/// This is intended for debugging and will impact performance negatively.
+/// [`Error::UnknownName`]: ../enum.Error.html#variant.UnknownName
+pub fn oversize_threshold() -> Result<usize> {
+    unsafe { get(OVERSIZE_THRESHOLD) }
+}
+
+/// A type providing access to the size in bytes above which allocations are served from a dedicated
+/// arena.
 ///
-/// The default is "false", unless `--enable-debug` was specified during build configuration, in
-/// which case the default is "true".
+/// This option was added in jemalloc 5.2. An [`Error::UnknownName`] error is returned with earlier
+/// versions.
 ///
 /// # Examples
 ///
@@ -249,44 +591,40 @@ pub fn junk() -> io::Result<&'static str> {
 /// extern crate jemallocator;
 /// extern crate jemalloc_ctl;
 ///
-/// use jemalloc_ctl::opt::Junk;
+/// use jemalloc_ctl::opt::OversizeThreshold;
 ///
 /// #[global_allocator]
 /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 ///
 /// fn main() {
-///     let junk = Junk::new().unwrap();
-///
-///     println!("junk filling: {}", junk.get().unwrap());
+///     if let Ok(oversize_threshold) = OversizeThreshold::new() {
+///         println!("oversize threshold: {}", oversize_threshold.get().unwrap());
+///     }
 /// }
 /// ```
+///
+/// [`Error::UnknownName`]: ../enum.Error.html#variant.UnknownName
 #[derive(Copy, Clone)]
-pub struct Junk([usize; 2]);
+pub struct OversizeThreshold(Mib<usize, [usize; 2]>);
 
-impl Junk {
-    /// Returns a new `Junk`.
-    pub fn new() -> io::Result<Junk> {
-        unsafe {
-            let mut mib = [0; 2];
-            name_to_mib(JUNK, &mut mib)?;
-            Ok(Junk(mib))
-        }
+impl OversizeThreshold {
+    /// Returns a new `OversizeThreshold`.
+    pub fn new() -> Result<OversizeThreshold> {
+        unsafe { Mib::new(OVERSIZE_THRESHOLD).map(OversizeThreshold) }
     }
 
-    /// Returns jemalloc's junk filling mode.
-    pub fn get(&self) -> io::Result<&'static str> {
-        unsafe { get_str_mib(&self.0) }
+    /// Returns the oversize threshold.
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
     }
 }
 
-const ZERO: *const c_char = b"opt.zero\0" as *const _ as *const _;
+const BACKGROUND_THREAD: &[u8] = b"opt.background_thread\0";
 
-/// Returns jemalloc's zeroing behavior.
-///
-/// Requires `--enable-fill` to have been specified during build configuration.
+/// Determines if background threads are enabled at startup.
 ///
-/// If enabled, jemalloc will initialize each byte of uninitialized allocated memory to 0. This is
-/// intended for debugging and will impact performance negatively. It is disabled by default.
+/// Background threads purge unused dirty and muzzy pages asynchronously. They can be enabled and
+/// disabled at run time with `background_thread`. This is disabled by default.
 ///
 /// # Examples
 ///
@@ -298,19 +636,17 @@ const ZERO: *const c_char = b"opt.zero\0" as *const _ as *const _;
 /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 ///
 /// fn main() {
-///     println!("zeroing: {}", jemalloc_ctl::opt::zero().unwrap());
+///     println!("background threads: {}", jemalloc_ctl::opt::background_thread().unwrap());
 /// }
 /// ```
-pub fn zero() -> io::Result<bool> {
-    unsafe { get(ZERO) }
+pub fn background_thread() -> Result<bool> {
+    unsafe { get(BACKGROUND_THREAD) }
 }
 
-/// A type providing access to jemalloc's zeroing behavior.
+/// A type determining if background threads are enabled at startup.
 ///
-/// Requires `--enable-fill` to have been specified during build configuration.
-///
-/// If enabled, jemalloc will initialize each byte of uninitialized allocated memory to 0. This is
-/// intended for debugging and will impact performance negatively. It is disabled by default.
+/// Background threads purge unused dirty and muzzy pages asynchronously. They can be enabled and
+/// disabled at run time with `background_thread`. This is disabled by default.
 ///
 /// # Examples
 ///
@@ -318,41 +654,37 @@ pub fn zero() -> io::Result<bool> {
 /// extern crate jemallocator;
 /// extern crate jemalloc_ctl;
 ///
-/// use jemalloc_ctl::opt::Zero;
+/// use jemalloc_ctl::opt::BackgroundThread;
 ///
 /// #[global_allocator]
 /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 ///
 /// fn main() {
-///     let zero = Zero::new().unwrap();
+///     let background_thread = BackgroundThread::new().unwrap();
 ///
-///     println!("zeroing: {}", zero.get().unwrap());
+///     println!("background threads: {}", background_thread.get().unwrap());
 /// }
 /// ```
-pub struct Zero([usize; 2]);
+#[derive(Copy, Clone)]
+pub struct BackgroundThread(Mib<bool, [usize; 2]>);
 
-impl Zero {
-    /// Returns a new `Zero`.
-    pub fn new() -> io::Result<Zero> {
-        unsafe {
-            let mut mib = [0; 2];
-            name_to_mib(ZERO, &mut mib)?;
-            Ok(Zero(mib))
-        }
+impl BackgroundThread {
+    /// Returns a new `BackgroundThread`.
+    pub fn new() -> Result<BackgroundThread> {
+        unsafe { Mib::new(BACKGROUND_THREAD).map(BackgroundThread) }
     }
 
-    /// Returns the jemalloc zeroing behavior.
-    pub fn get(&self) -> io::Result<bool> {
-        unsafe { get_mib(&self.0) }
+    /// Returns the initial background thread state.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
     }
 }
 
-const TCACHE: *const c_char = b"opt.tcache\0" as *const _ as *const _;
+const MAX_BACKGROUND_THREADS: &[u8] = b"opt.max_background_threads\0";
 
-/// Determines if thread-local allocation caching is enabled.
+/// Returns the maximum number of background threads that will be created.
 ///
-/// Thread-specific caching allows many allocations to be satisfied without performing any thread
-/// synchronization, at the cost of increased memory use. This is enabled by default.
+/// The default is the number of CPUs.
 ///
 /// # Examples
 ///
@@ -364,17 +696,16 @@ const TCACHE: *const c_char = b"opt.tcache\0" as *const _ as *const _;
 /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 ///
 /// fn main() {
-///     println!("thread-local caching: {}", jemalloc_ctl::opt::tcache().unwrap());
+///     println!("max threads: {}", jemalloc_ctl::opt::max_background_threads().unwrap());
 /// }
 /// ```
-pub fn tcache() -> io::Result<bool> {
-    unsafe { get(TCACHE) }
+pub fn max_background_threads() -> Result<usize> {
+    unsafe { get(MAX_BACKGROUND_THREADS) }
 }
 
-/// A type providing access to thread-local allocation caching behavior.
+/// A type providing access to the maximum number of background threads that will be created.
 ///
-/// Thread-specific caching allows many allocations to be satisfied without performing any thread
-/// synchronization, at the cost of increased memory use. This is enabled by default.
+/// The default is the number of CPUs.
 ///
 /// # Examples
 ///
@@ -382,41 +713,38 @@ pub fn tcache() -> io::Result<bool> {
 /// extern crate jemallocator;
 /// extern crate jemalloc_ctl;
 ///
-/// use jemalloc_ctl::opt::Tcache;
+/// use jemalloc_ctl::opt::MaxBackgroundThreads;
 ///
 /// #[global_allocator]
 /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 ///
 /// fn main() {
-///     let tcache = Tcache::new().unwrap();
+///     let max_background_threads = MaxBackgroundThreads::new().unwrap();
 ///
-///     println!("thread-local caching: {}", tcache.get().unwrap());
+///     println!("max background threads: {}", max_background_threads.get().unwrap());
 /// }
 /// ```
-pub struct Tcache([usize; 2]);
+#[derive(Copy, Clone)]
+pub struct MaxBackgroundThreads(Mib<usize, [usize; 2]>);
 
-impl Tcache {
-    /// Returns a new `Tcache`.
-    pub fn new() -> io::Result<Tcache> {
-        unsafe {
-            let mut mib = [0; 2];
-            name_to_mib(TCACHE, &mut mib)?;
-            Ok(Tcache(mib))
-        }
+impl MaxBackgroundThreads {
+    /// Returns a new `MaxBackgroundThreads`.
+    pub fn new() -> Result<MaxBackgroundThreads> {
+        unsafe { Mib::new(MAX_BACKGROUND_THREADS).map(MaxBackgroundThreads) }
     }
 
-    /// Returns the thread-local caching behavior.
-    pub fn get(&self) -> io::Result<bool> {
-        unsafe { get_mib(&self.0) }
+    /// Returns the maximum number of background threads.
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
     }
 }
 
-const LG_TCACHE_MAX: *const c_char = b"opt.lg_tcache_max\0" as *const _ as *const _;
+const DIRTY_DECAY_MS: &[u8] = b"opt.dirty_decay_ms\0";
 
-/// Returns the maximum size class (log base 2) to cache in the thread-specific cache (tcache).
+/// Returns the initial time it takes for unused dirty pages to be purged.
 ///
-/// At a minimum, all small size classes are cached, and at a maximum all large size classes are
-/// cached. The default maximum is 32 KiB (2^15).
+/// This is the default for arenas created after startup, which can be changed at run time with
+/// `arenas::set_default_dirty_decay_ms`. The default is 10 seconds.
 ///
 /// # Examples
 ///
@@ -428,18 +756,17 @@ const LG_TCACHE_MAX: *const c_char = b"opt.lg_tcache_max\0" as *const _ as *cons
 /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 ///
 /// fn main() {
-///     println!("max cached allocation size: {}", 1 << jemalloc_ctl::opt::lg_tcache_max().unwrap());
+///     println!("dirty decay time: {:?}", jemalloc_ctl::opt::dirty_decay_ms().unwrap());
 /// }
 /// ```
-pub fn lg_tcache_max() -> io::Result<usize> {
-    unsafe { get(LG_TCACHE_MAX) }
+pub fn dirty_decay_ms() -> Result<DecayTime> {
+    unsafe { get(DIRTY_DECAY_MS).map(DecayTime::from_raw) }
 }
 
-/// A type providing access to the maximum size class (log base 2) to cache in the thread-specific
-/// cache (tcache).
+/// A type providing access to the initial time it takes for unused dirty pages to be purged.
 ///
-/// At a minimum, all small size classes are cached, and at a maximum all large size classes are
-/// cached. The default maximum is 32 KiB (2^15).
+/// This is the default for arenas created after startup, which can be changed at run time with
+/// `arenas::set_default_dirty_decay_ms`. The default is 10 seconds.
 ///
 /// # Examples
 ///
@@ -447,32 +774,1469 @@ pub fn lg_tcache_max() -> io::Result<usize> {
 /// extern crate jemallocator;
 /// extern crate jemalloc_ctl;
 ///
-/// use jemalloc_ctl::opt::LgTcacheMax;
+/// use jemalloc_ctl::opt::DirtyDecayMs;
 ///
 /// #[global_allocator]
 /// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
 ///
 /// fn main() {
-///     let lg_tcache_max = LgTcacheMax::new().unwrap();
+///     let dirty_decay_ms = DirtyDecayMs::new().unwrap();
 ///
-///     println!("max cached allocation size: {}", 1 << lg_tcache_max.get().unwrap());
+///     println!("dirty decay time: {:?}", dirty_decay_ms.get().unwrap());
 /// }
 /// ```
 #[derive(Copy, Clone)]
-pub struct LgTcacheMax([usize; 2]);
+pub struct DirtyDecayMs(Mib<ssize_t, [usize; 2]>);
 
-impl LgTcacheMax {
-    /// Returns a new `LgTcacheMax`.
-    pub fn new() -> io::Result<LgTcacheMax> {
-        unsafe {
-            let mut mib = [0; 2];
-            name_to_mib(LG_TCACHE_MAX, &mut mib)?;
-            Ok(LgTcacheMax(mib))
-        }
+impl DirtyDecayMs {
+    /// Returns a new `DirtyDecayMs`.
+    pub fn new() -> Result<DirtyDecayMs> {
+        unsafe { Mib::new(DIRTY_DECAY_MS).map(DirtyDecayMs) }
     }
 
-    /// Returns the maximum cached size class.
-    pub fn get(&self) -> io::Result<usize> {
-        unsafe { get_mib(&self.0) }
+    /// Returns the initial dirty page decay time.
+    pub fn get(&self) -> Result<DecayTime> {
+        self.0.read().map(DecayTime::from_raw)
+    }
+}
+
+const MUZZY_DECAY_MS: &[u8] = b"opt.muzzy_decay_ms\0";
+
+/// Returns the initial time it takes for unused muzzy pages to be purged.
+///
+/// This is the default for arenas created after startup, which can be changed at run time with
+/// `arenas::set_default_muzzy_decay_ms`. The default is 10 seconds.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("muzzy decay time: {:?}", jemalloc_ctl::opt::muzzy_decay_ms().unwrap());
+/// }
+/// ```
+pub fn muzzy_decay_ms() -> Result<DecayTime> {
+    unsafe { get(MUZZY_DECAY_MS).map(DecayTime::from_raw) }
+}
+
+/// A type providing access to the initial time it takes for unused muzzy pages to be purged.
+///
+/// This is the default for arenas created after startup, which can be changed at run time with
+/// `arenas::set_default_muzzy_decay_ms`. The default is 10 seconds.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::MuzzyDecayMs;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let muzzy_decay_ms = MuzzyDecayMs::new().unwrap();
+///
+///     println!("muzzy decay time: {:?}", muzzy_decay_ms.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct MuzzyDecayMs(Mib<ssize_t, [usize; 2]>);
+
+impl MuzzyDecayMs {
+    /// Returns a new `MuzzyDecayMs`.
+    pub fn new() -> Result<MuzzyDecayMs> {
+        unsafe { Mib::new(MUZZY_DECAY_MS).map(MuzzyDecayMs) }
+    }
+
+    /// Returns the initial muzzy page decay time.
+    pub fn get(&self) -> Result<DecayTime> {
+        self.0.read().map(DecayTime::from_raw)
+    }
+}
+
+const JUNK: &[u8] = b"opt.junk\0";
+
+/// Returns jemalloc's junk filling mode.
+///
+/// Requires `--enable-fill` to have been specified during build configuration.
+///
+/// If set to `Alloc`, each byte of uninitialized allocated memory will be set to `0xa5`. If set to
+/// `Free`, each byte of deallocated memory will be set to `0x5a`. If set to `Enabled`, both
+/// allocated and deallocated memory will be initialized, and if set to `Disabled` junk filling
+/// will be disabled. This is intended for debugging and will impact performance negatively.
+///
+/// The default is `Disabled`, unless `--enable-debug` was specified during build configuration,
+/// in which case the default is `Enabled`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("junk filling: {}", jemalloc_ctl::opt::junk().unwrap());
+/// }
+/// ```
+pub fn junk() -> Result<JunkMode> {
+    unsafe { get_str(JUNK).and_then(|mode| parse(JUNK, mode)) }
+}
+
+/// A type providing access to jemalloc's junk filling mode.
+///
+/// Requires `--enable-fill` to have been specified during build configuration.
+///
+/// If set to `Alloc`, each byte of uninitialized allocated memory will be set to `0xa5`. If set to
+/// `Free`, each byte of deallocated memory will be set to `0x5a`. If set to `Enabled`, both
+/// allocated and deallocated memory will be initialized, and if set to `Disabled` junk filling
+/// will be disabled. This is intended for debugging and will impact performance negatively.
+///
+/// The default is `Disabled`, unless `--enable-debug` was specified during build configuration,
+/// in which case the default is `Enabled`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::Junk;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let junk = Junk::new().unwrap();
+///
+///     println!("junk filling: {}", junk.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Junk(MibStr<[usize; 2]>);
+
+impl Junk {
+    /// Returns a new `Junk`.
+    pub fn new() -> Result<Junk> {
+        unsafe { MibStr::new(JUNK).map(Junk) }
+    }
+
+    /// Returns jemalloc's junk filling mode.
+    pub fn get(&self) -> Result<JunkMode> {
+        self.0.read().and_then(|mode| parse(JUNK, mode))
+    }
+}
+
+modes! {
+    /// jemalloc's junk filling mode.
+    pub enum JunkMode {
+        /// Junk filling is disabled.
+        Disabled => "false",
+        /// Uninitialized allocated memory is junk filled.
+        Alloc => "alloc",
+        /// Deallocated memory is junk filled.
+        Free => "free",
+        /// Both uninitialized allocated memory and deallocated memory are junk filled.
+        Enabled => "true",
+    }
+}
+
+const ZERO: &[u8] = b"opt.zero\0";
+
+/// Returns jemalloc's zeroing behavior.
+///
+/// Requires `--enable-fill` to have been specified during build configuration.
+///
+/// If enabled, jemalloc will initialize each byte of uninitialized allocated memory to 0. This is
+/// intended for debugging and will impact performance negatively. It is disabled by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("zeroing: {}", jemalloc_ctl::opt::zero().unwrap());
+/// }
+/// ```
+pub fn zero() -> Result<bool> {
+    unsafe { get(ZERO) }
+}
+
+/// A type providing access to jemalloc's zeroing behavior.
+///
+/// Requires `--enable-fill` to have been specified during build configuration.
+///
+/// If enabled, jemalloc will initialize each byte of uninitialized allocated memory to 0. This is
+/// intended for debugging and will impact performance negatively. It is disabled by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::Zero;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let zero = Zero::new().unwrap();
+///
+///     println!("zeroing: {}", zero.get().unwrap());
+/// }
+/// ```
+pub struct Zero(Mib<bool, [usize; 2]>);
+
+impl Zero {
+    /// Returns a new `Zero`.
+    pub fn new() -> Result<Zero> {
+        unsafe { Mib::new(ZERO).map(Zero) }
+    }
+
+    /// Returns the jemalloc zeroing behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const UTRACE: &[u8] = b"opt.utrace\0";
+
+/// Determines if allocation calls are traced with `utrace(2)`.
+///
+/// Requires `--enable-utrace` to have been specified during build configuration. This is disabled
+/// by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(utrace) = jemalloc_ctl::opt::utrace() {
+///         println!("utrace: {}", utrace);
+///     }
+/// }
+/// ```
+pub fn utrace() -> Result<bool> {
+    unsafe { get(UTRACE) }
+}
+
+/// A type determining if allocation calls are traced with `utrace(2)`.
+///
+/// Requires `--enable-utrace` to have been specified during build configuration. This is disabled
+/// by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::Utrace;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let utrace = Utrace::new().unwrap();
+///
+///     if let Ok(value) = utrace.get() {
+///         println!("utrace: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Utrace(Mib<bool, [usize; 2]>);
+
+impl Utrace {
+    /// Returns a new `Utrace`.
+    pub fn new() -> Result<Utrace> {
+        unsafe { Mib::new(UTRACE).map(Utrace) }
+    }
+
+    /// Returns the `utrace(2)` tracing behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const XMALLOC: &[u8] = b"opt.xmalloc\0";
+
+/// Determines if jemalloc will call `abort(3)` rather than return an error when out of memory.
+///
+/// Requires `--enable-xmalloc` to have been specified during build configuration. This is disabled
+/// by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(xmalloc) = jemalloc_ctl::opt::xmalloc() {
+///         println!("abort on out of memory: {}", xmalloc);
+///     }
+/// }
+/// ```
+pub fn xmalloc() -> Result<bool> {
+    unsafe { get(XMALLOC) }
+}
+
+/// A type determining if jemalloc will call `abort(3)` rather than return an error when out of
+/// memory.
+///
+/// Requires `--enable-xmalloc` to have been specified during build configuration. This is disabled
+/// by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::Xmalloc;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let xmalloc = Xmalloc::new().unwrap();
+///
+///     if let Ok(value) = xmalloc.get() {
+///         println!("abort on out of memory: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Xmalloc(Mib<bool, [usize; 2]>);
+
+impl Xmalloc {
+    /// Returns a new `Xmalloc`.
+    pub fn new() -> Result<Xmalloc> {
+        unsafe { Mib::new(XMALLOC).map(Xmalloc) }
+    }
+
+    /// Returns the abort-on-out-of-memory behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const TCACHE: &[u8] = b"opt.tcache\0";
+
+/// Determines if thread-local allocation caching is enabled.
+///
+/// Thread-specific caching allows many allocations to be satisfied without performing any thread
+/// synchronization, at the cost of increased memory use. This is enabled by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("thread-local caching: {}", jemalloc_ctl::opt::tcache().unwrap());
+/// }
+/// ```
+pub fn tcache() -> Result<bool> {
+    unsafe { get(TCACHE) }
+}
+
+/// A type providing access to thread-local allocation caching behavior.
+///
+/// Thread-specific caching allows many allocations to be satisfied without performing any thread
+/// synchronization, at the cost of increased memory use. This is enabled by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::Tcache;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let tcache = Tcache::new().unwrap();
+///
+///     println!("thread-local caching: {}", tcache.get().unwrap());
+/// }
+/// ```
+pub struct Tcache(Mib<bool, [usize; 2]>);
+
+impl Tcache {
+    /// Returns a new `Tcache`.
+    pub fn new() -> Result<Tcache> {
+        unsafe { Mib::new(TCACHE).map(Tcache) }
+    }
+
+    /// Returns the thread-local caching behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const LG_TCACHE_MAX: &[u8] = b"opt.lg_tcache_max\0";
+
+/// Returns the maximum size class (log base 2) to cache in the thread-specific cache (tcache).
+///
+/// At a minimum, all small size classes are cached, and at a maximum all large size classes are
+/// cached. The default maximum is 32 KiB (2^15). A value of -1 caches only small size classes.
+///
+/// jemalloc stores this option as an `ssize_t`. Earlier versions of this crate read it as a
+/// `usize`, which reported -1 as `usize::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("max cached size class: 2^{}", jemalloc_ctl::opt::lg_tcache_max().unwrap());
+/// }
+/// ```
+pub fn lg_tcache_max() -> Result<ssize_t> {
+    unsafe { get(LG_TCACHE_MAX) }
+}
+
+/// A type providing access to the maximum size class (log base 2) to cache in the thread-specific
+/// cache (tcache).
+///
+/// At a minimum, all small size classes are cached, and at a maximum all large size classes are
+/// cached. The default maximum is 32 KiB (2^15). A value of -1 caches only small size classes.
+///
+/// jemalloc stores this option as an `ssize_t`. Earlier versions of this crate read it as a
+/// `usize`, which reported -1 as `usize::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::LgTcacheMax;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let lg_tcache_max = LgTcacheMax::new().unwrap();
+///
+///     println!("max cached size class: 2^{}", lg_tcache_max.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct LgTcacheMax(Mib<ssize_t, [usize; 2]>);
+
+impl LgTcacheMax {
+    /// Returns a new `LgTcacheMax`.
+    pub fn new() -> Result<LgTcacheMax> {
+        unsafe { Mib::new(LG_TCACHE_MAX).map(LgTcacheMax) }
+    }
+
+    /// Returns the maximum cached size class.
+    pub fn get(&self) -> Result<ssize_t> {
+        self.0.read()
+    }
+}
+
+const THP: &[u8] = b"opt.thp\0";
+
+/// Returns the transparent huge page (THP) mode.
+///
+/// With `Always` or `Never`, jemalloc explicitly enables or disables huge pages for its mappings
+/// with `madvise(2)`. The default is `Default`, which leaves the system setting in effect, and
+/// `NotSupported` is reported if the system does not support transparent huge pages.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("THP mode: {}", jemalloc_ctl::opt::thp().unwrap());
+/// }
+/// ```
+pub fn thp() -> Result<ThpMode> {
+    unsafe { get_str(THP).and_then(|mode| parse(THP, mode)) }
+}
+
+/// A type providing access to the transparent huge page (THP) mode.
+///
+/// With `Always` or `Never`, jemalloc explicitly enables or disables huge pages for its mappings
+/// with `madvise(2)`. The default is `Default`, which leaves the system setting in effect, and
+/// `NotSupported` is reported if the system does not support transparent huge pages.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::Thp;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let thp = Thp::new().unwrap();
+///
+///     println!("THP mode: {}", thp.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Thp(MibStr<[usize; 2]>);
+
+impl Thp {
+    /// Returns a new `Thp`.
+    pub fn new() -> Result<Thp> {
+        unsafe { MibStr::new(THP).map(Thp) }
+    }
+
+    /// Returns the THP mode.
+    pub fn get(&self) -> Result<ThpMode> {
+        self.0.read().and_then(|mode| parse(THP, mode))
+    }
+}
+
+modes! {
+    /// The transparent huge page mode.
+    pub enum ThpMode {
+        /// The system setting is left in effect.
+        Default => "default",
+        /// Huge pages are enabled for jemalloc's mappings.
+        Always => "always",
+        /// Huge pages are disabled for jemalloc's mappings.
+        Never => "never",
+        /// The system does not support transparent huge pages.
+        NotSupported => "not supported",
+    }
+}
+
+const LG_EXTENT_MAX_ACTIVE_FIT: &[u8] = b"opt.lg_extent_max_active_fit\0";
+
+/// Returns the maximum size ratio (log base 2) between a reused extent and the allocation it
+/// serves.
+///
+/// Reusing a much larger extent for a small allocation leads to fragmentation, so larger extents
+/// are not used. The default is 6, i.e. a ratio of 64.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("max fit ratio: {}", jemalloc_ctl::opt::lg_extent_max_active_fit().unwrap());
+/// }
+/// ```
+pub fn lg_extent_max_active_fit() -> Result<usize> {
+    unsafe { get(LG_EXTENT_MAX_ACTIVE_FIT) }
+}
+
+/// A type providing access to the maximum size ratio (log base 2) between a reused extent and the
+/// allocation it serves.
+///
+/// Reusing a much larger extent for a small allocation leads to fragmentation, so larger extents
+/// are not used. The default is 6, i.e. a ratio of 64.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::LgExtentMaxActiveFit;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let lg_extent_max_active_fit = LgExtentMaxActiveFit::new().unwrap();
+///
+///     println!("max active fit ratio: {}", lg_extent_max_active_fit.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct LgExtentMaxActiveFit(Mib<usize, [usize; 2]>);
+
+impl LgExtentMaxActiveFit {
+    /// Returns a new `LgExtentMaxActiveFit`.
+    pub fn new() -> Result<LgExtentMaxActiveFit> {
+        unsafe { Mib::new(LG_EXTENT_MAX_ACTIVE_FIT).map(LgExtentMaxActiveFit) }
+    }
+
+    /// Returns the maximum extent size ratio.
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
+    }
+}
+
+const PROF: &[u8] = b"opt.prof\0";
+
+/// Determines if heap profiling is enabled.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(prof) = jemalloc_ctl::opt::prof() {
+///         println!("heap profiling: {}", prof);
+///     }
+/// }
+/// ```
+pub fn prof() -> Result<bool> {
+    unsafe { get(PROF) }
+}
+
+/// A type determining if heap profiling is enabled.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::Prof;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof = Prof::new().unwrap();
+///
+///     if let Ok(value) = prof.get() {
+///         println!("heap profiling: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Prof(Mib<bool, [usize; 2]>);
+
+impl Prof {
+    /// Returns a new `Prof`.
+    pub fn new() -> Result<Prof> {
+        unsafe { Mib::new(PROF).map(Prof) }
+    }
+
+    /// Returns the heap profiling state.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const PROF_PREFIX: &[u8] = b"opt.prof_prefix\0";
+
+/// Returns the filename prefix for profile dumps.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. The default is
+/// "jeprof".
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(prof_prefix) = jemalloc_ctl::opt::prof_prefix() {
+///         println!("profile prefix: {}", prof_prefix);
+///     }
+/// }
+/// ```
+pub fn prof_prefix() -> Result<&'static str> {
+    unsafe { get_str(PROF_PREFIX) }
+}
+
+/// A type providing access to the filename prefix for profile dumps.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. The default is
+/// "jeprof".
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::ProfPrefix;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_prefix = ProfPrefix::new().unwrap();
+///
+///     if let Ok(value) = prof_prefix.get() {
+///         println!("profile prefix: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfPrefix(MibStr<[usize; 2]>);
+
+impl ProfPrefix {
+    /// Returns a new `ProfPrefix`.
+    pub fn new() -> Result<ProfPrefix> {
+        unsafe { MibStr::new(PROF_PREFIX).map(ProfPrefix) }
+    }
+
+    /// Returns the profile dump filename prefix.
+    pub fn get(&self) -> Result<&'static str> {
+        self.0.read()
+    }
+}
+
+const PROF_ACTIVE: &[u8] = b"opt.prof_active\0";
+
+/// Determines if sampling is active at startup.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. Sampling can be
+/// toggled at run time with `prof::set_active`. This is enabled by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(prof_active) = jemalloc_ctl::opt::prof_active() {
+///         println!("initial sampling: {}", prof_active);
+///     }
+/// }
+/// ```
+pub fn prof_active() -> Result<bool> {
+    unsafe { get(PROF_ACTIVE) }
+}
+
+/// A type determining if sampling is active at startup.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. Sampling can be
+/// toggled at run time with `prof::set_active`. This is enabled by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::ProfActive;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_active = ProfActive::new().unwrap();
+///
+///     if let Ok(value) = prof_active.get() {
+///         println!("initial sampling: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfActive(Mib<bool, [usize; 2]>);
+
+impl ProfActive {
+    /// Returns a new `ProfActive`.
+    pub fn new() -> Result<ProfActive> {
+        unsafe { Mib::new(PROF_ACTIVE).map(ProfActive) }
+    }
+
+    /// Returns the initial sampling state.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const PROF_THREAD_ACTIVE_INIT: &[u8] = b"opt.prof_thread_active_init\0";
+
+/// Determines if sampling is initially active for newly created threads.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is enabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(prof_thread_active_init) = jemalloc_ctl::opt::prof_thread_active_init() {
+///         println!("new thread sampling: {}", prof_thread_active_init);
+///     }
+/// }
+/// ```
+pub fn prof_thread_active_init() -> Result<bool> {
+    unsafe { get(PROF_THREAD_ACTIVE_INIT) }
+}
+
+/// A type determining if sampling is initially active for newly created threads.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is enabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::ProfThreadActiveInit;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_thread_active_init = ProfThreadActiveInit::new().unwrap();
+///
+///     if let Ok(value) = prof_thread_active_init.get() {
+///         println!("new thread sampling: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfThreadActiveInit(Mib<bool, [usize; 2]>);
+
+impl ProfThreadActiveInit {
+    /// Returns a new `ProfThreadActiveInit`.
+    pub fn new() -> Result<ProfThreadActiveInit> {
+        unsafe { Mib::new(PROF_THREAD_ACTIVE_INIT).map(ProfThreadActiveInit) }
+    }
+
+    /// Returns the initial sampling state of new threads.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const LG_PROF_SAMPLE: &[u8] = b"opt.lg_prof_sample\0";
+
+/// Returns the average interval (log base 2) in bytes between allocation samples.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. The default is 512
+/// KiB (2^19).
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(lg_prof_sample) = jemalloc_ctl::opt::lg_prof_sample() {
+///         println!("sample interval: {}", lg_prof_sample);
+///     }
+/// }
+/// ```
+pub fn lg_prof_sample() -> Result<usize> {
+    unsafe { get(LG_PROF_SAMPLE) }
+}
+
+/// A type providing access to the average interval (log base 2) in bytes between allocation
+/// samples.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. The default is 512
+/// KiB (2^19).
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::LgProfSample;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let lg_prof_sample = LgProfSample::new().unwrap();
+///
+///     if let Ok(value) = lg_prof_sample.get() {
+///         println!("sample interval: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct LgProfSample(Mib<usize, [usize; 2]>);
+
+impl LgProfSample {
+    /// Returns a new `LgProfSample`.
+    pub fn new() -> Result<LgProfSample> {
+        unsafe { Mib::new(LG_PROF_SAMPLE).map(LgProfSample) }
+    }
+
+    /// Returns the sample interval.
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
+    }
+}
+
+const LG_PROF_INTERVAL: &[u8] = b"opt.lg_prof_interval\0";
+
+/// Returns the average interval (log base 2) in bytes of allocation activity between profile dumps.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. A value of -1, the
+/// default, disables interval-triggered dumps.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(lg_prof_interval) = jemalloc_ctl::opt::lg_prof_interval() {
+///         println!("dump interval: {}", lg_prof_interval);
+///     }
+/// }
+/// ```
+pub fn lg_prof_interval() -> Result<ssize_t> {
+    unsafe { get(LG_PROF_INTERVAL) }
+}
+
+/// A type providing access to the average interval (log base 2) in bytes of allocation activity
+/// between profile dumps.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. A value of -1, the
+/// default, disables interval-triggered dumps.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::LgProfInterval;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let lg_prof_interval = LgProfInterval::new().unwrap();
+///
+///     if let Ok(value) = lg_prof_interval.get() {
+///         println!("dump interval: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct LgProfInterval(Mib<ssize_t, [usize; 2]>);
+
+impl LgProfInterval {
+    /// Returns a new `LgProfInterval`.
+    pub fn new() -> Result<LgProfInterval> {
+        unsafe { Mib::new(LG_PROF_INTERVAL).map(LgProfInterval) }
+    }
+
+    /// Returns the dump interval.
+    pub fn get(&self) -> Result<ssize_t> {
+        self.0.read()
+    }
+}
+
+const PROF_GDUMP: &[u8] = b"opt.prof_gdump\0";
+
+/// Determines if a profile is dumped every time the total virtual memory exceeds its previous
+/// maximum.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(prof_gdump) = jemalloc_ctl::opt::prof_gdump() {
+///         println!("high water mark dumps: {}", prof_gdump);
+///     }
+/// }
+/// ```
+pub fn prof_gdump() -> Result<bool> {
+    unsafe { get(PROF_GDUMP) }
+}
+
+/// A type determining if a profile is dumped every time the total virtual memory exceeds its
+/// previous maximum.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::ProfGdump;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_gdump = ProfGdump::new().unwrap();
+///
+///     if let Ok(value) = prof_gdump.get() {
+///         println!("high water mark dumps: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfGdump(Mib<bool, [usize; 2]>);
+
+impl ProfGdump {
+    /// Returns a new `ProfGdump`.
+    pub fn new() -> Result<ProfGdump> {
+        unsafe { Mib::new(PROF_GDUMP).map(ProfGdump) }
+    }
+
+    /// Returns the initial high water mark dump state.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const PROF_FINAL: &[u8] = b"opt.prof_final\0";
+
+/// Determines if a final profile is dumped at exit.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(prof_final) = jemalloc_ctl::opt::prof_final() {
+///         println!("final dump: {}", prof_final);
+///     }
+/// }
+/// ```
+pub fn prof_final() -> Result<bool> {
+    unsafe { get(PROF_FINAL) }
+}
+
+/// A type determining if a final profile is dumped at exit.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::ProfFinal;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_final = ProfFinal::new().unwrap();
+///
+///     if let Ok(value) = prof_final.get() {
+///         println!("final dump: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfFinal(Mib<bool, [usize; 2]>);
+
+impl ProfFinal {
+    /// Returns a new `ProfFinal`.
+    pub fn new() -> Result<ProfFinal> {
+        unsafe { Mib::new(PROF_FINAL).map(ProfFinal) }
+    }
+
+    /// Returns the final dump behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const PROF_LEAK: &[u8] = b"opt.prof_leak\0";
+
+/// Determines if memory leaks are reported at exit.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(prof_leak) = jemalloc_ctl::opt::prof_leak() {
+///         println!("leak reporting: {}", prof_leak);
+///     }
+/// }
+/// ```
+pub fn prof_leak() -> Result<bool> {
+    unsafe { get(PROF_LEAK) }
+}
+
+/// A type determining if memory leaks are reported at exit.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::ProfLeak;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_leak = ProfLeak::new().unwrap();
+///
+///     if let Ok(value) = prof_leak.get() {
+///         println!("leak reporting: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfLeak(Mib<bool, [usize; 2]>);
+
+impl ProfLeak {
+    /// Returns a new `ProfLeak`.
+    pub fn new() -> Result<ProfLeak> {
+        unsafe { Mib::new(PROF_LEAK).map(ProfLeak) }
+    }
+
+    /// Returns the leak reporting behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const PROF_ACCUM: &[u8] = b"opt.prof_accum\0";
+
+/// Determines if profile dumps include cumulative statistics.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(prof_accum) = jemalloc_ctl::opt::prof_accum() {
+///         println!("cumulative statistics: {}", prof_accum);
+///     }
+/// }
+/// ```
+pub fn prof_accum() -> Result<bool> {
+    unsafe { get(PROF_ACCUM) }
+}
+
+/// A type determining if profile dumps include cumulative statistics.
+///
+/// Requires `--enable-prof` to have been specified during build configuration. This is disabled by
+/// default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::ProfAccum;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_accum = ProfAccum::new().unwrap();
+///
+///     if let Ok(value) = prof_accum.get() {
+///         println!("cumulative statistics: {}", value);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfAccum(Mib<bool, [usize; 2]>);
+
+impl ProfAccum {
+    /// Returns a new `ProfAccum`.
+    pub fn new() -> Result<ProfAccum> {
+        unsafe { Mib::new(PROF_ACCUM).map(ProfAccum) }
+    }
+
+    /// Returns the cumulative statistics behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const STATS_PRINT: &[u8] = b"opt.stats_print\0";
+
+/// Determines if statistics are printed at exit.
+///
+/// The statistics are printed with `malloc_stats_print`, using the options in `stats_print_opts`.
+/// This is disabled by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("print statistics at exit: {}", jemalloc_ctl::opt::stats_print().unwrap());
+/// }
+/// ```
+pub fn stats_print() -> Result<bool> {
+    unsafe { get(STATS_PRINT) }
+}
+
+/// A type determining if statistics are printed at exit.
+///
+/// The statistics are printed with `malloc_stats_print`, using the options in `stats_print_opts`.
+/// This is disabled by default.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::StatsPrint;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let stats_print = StatsPrint::new().unwrap();
+///
+///     println!("print statistics at exit: {}", stats_print.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct StatsPrint(Mib<bool, [usize; 2]>);
+
+impl StatsPrint {
+    /// Returns a new `StatsPrint`.
+    pub fn new() -> Result<StatsPrint> {
+        unsafe { Mib::new(STATS_PRINT).map(StatsPrint) }
+    }
+
+    /// Returns the exit statistics behavior.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+}
+
+const STATS_PRINT_OPTS: &[u8] = b"opt.stats_print_opts\0";
+
+/// Returns the options passed to `malloc_stats_print` at exit.
+///
+/// See `stats_print::Options` for the meaning of each character. The default is empty.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("exit statistics options: {}", jemalloc_ctl::opt::stats_print_opts().unwrap());
+/// }
+/// ```
+pub fn stats_print_opts() -> Result<&'static str> {
+    unsafe { get_str(STATS_PRINT_OPTS) }
+}
+
+/// A type providing access to the options passed to `malloc_stats_print` at exit.
+///
+/// See `stats_print::Options` for the meaning of each character. The default is empty.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::opt::StatsPrintOpts;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let stats_print_opts = StatsPrintOpts::new().unwrap();
+///
+///     println!("exit statistics options: {}", stats_print_opts.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct StatsPrintOpts(MibStr<[usize; 2]>);
+
+impl StatsPrintOpts {
+    /// Returns a new `StatsPrintOpts`.
+    pub fn new() -> Result<StatsPrintOpts> {
+        unsafe { MibStr::new(STATS_PRINT_OPTS).map(StatsPrintOpts) }
+    }
+
+    /// Returns the exit statistics options.
+    pub fn get(&self) -> Result<&'static str> {
+        self.0.read()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn modes_round_trip() {
+        for &mode in &[DssMode::Disabled, DssMode::Primary, DssMode::Secondary] {
+            assert_eq!(mode.as_str().parse::<DssMode>(), Ok(mode));
+        }
+        assert_eq!("true".parse::<JunkMode>(), Ok(JunkMode::Enabled));
+        assert_eq!(
+            "not supported".parse::<ThpMode>(),
+            Ok(ThpMode::NotSupported)
+        );
+        assert_eq!(PercpuArenaMode::Phycpu.to_string(), "phycpu");
+        assert!("sometimes".parse::<MetadataThpMode>().is_err());
+    }
+
+    #[test]
+    fn unexpected_value() {
+        match parse::<ThpMode>(THP, "sometimes") {
+            Err(Error::UnexpectedValue {
+                ref name,
+                ref value,
+            }) if name == "opt.thp" && value == "sometimes" => {}
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn read_options() {
+        dss().unwrap();
+        junk().unwrap();
+        metadata_thp().unwrap();
+        percpu_arena().unwrap();
+        thp().unwrap();
+        match oversize_threshold() {
+            Ok(_) | Err(Error::UnknownName { .. }) => {}
+            r => panic!("unexpected result {:?}", r),
+        }
+        assert_eq!(
+            dirty_decay_ms().unwrap(),
+            ::arenas::default_dirty_decay_ms().unwrap()
+        );
+
+        if !::config::prof().unwrap() {
+            match prof() {
+                Err(Error::NotConfigured { ref name }) => assert_eq!(name, "opt.prof"),
+                r => panic!("unexpected result {:?}", r),
+            }
+        }
     }
 }
@@ -0,0 +1,581 @@
+//! Heap profiling operations.
+//!
+//! These operations are only available if jemalloc was built with profiling support (the
+//! `profiling` feature of `jemallocator`), and an [`Error::NotConfigured`] error is returned
+//! otherwise. Most of them additionally require profiling to have been enabled at startup with the
+//! `prof` option, e.g. by running the program with `MALLOC_CONF=prof:true`.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate jemallocator;
+//! extern crate jemalloc_ctl;
+//!
+//! use jemalloc_ctl::prof;
+//!
+//! #[global_allocator]
+//! static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+//!
+//! fn main() {
+//!     match prof::set_active(true) {
+//!         Ok(()) => {
+//!             let _buf = vec![0u8; 1024 * 1024];
+//!             prof::dump(None).unwrap();
+//!         }
+//!         Err(e) => println!("heap profiling is unavailable: {}", e),
+//!     }
+//! }
+//! ```
+//!
+//! [`Error::NotConfigured`]: ../enum.Error.html#variant.NotConfigured
+use libc::c_char;
+use std::path::Path;
+use std::ptr;
+
+use {c_str, get, name_string, run, run_mib, set, Mib, Result};
+
+const ACTIVE: &[u8] = b"prof.active\0";
+
+/// Determines if sampling is currently active.
+///
+/// This corresponds to `prof.active` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(active) = jemalloc_ctl::prof::active() {
+///         println!("sampling active: {}", active);
+///     }
+/// }
+/// ```
+pub fn active() -> Result<bool> {
+    unsafe { get(ACTIVE) }
+}
+
+/// Activates or deactivates sampling.
+///
+/// Sampling can only be activated if profiling was enabled at startup.
+///
+/// This corresponds to `prof.active` in jemalloc's API.
+pub fn set_active(active: bool) -> Result<()> {
+    unsafe { set(ACTIVE, active) }
+}
+
+/// A type providing access to the state of sampling.
+///
+/// This corresponds to `prof.active` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::prof::Active;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let active = Active::new().unwrap();
+///
+///     if active.set(false).is_ok() {
+///         assert!(!active.get().unwrap());
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Active(Mib<bool, [usize; 2]>);
+
+impl Active {
+    /// Returns a new `Active`.
+    pub fn new() -> Result<Active> {
+        unsafe { Mib::new(ACTIVE).map(Active) }
+    }
+
+    /// Determines if sampling is currently active.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+
+    /// Activates or deactivates sampling.
+    pub fn set(&self, active: bool) -> Result<()> {
+        self.0.write(active)
+    }
+}
+
+const DUMP: &[u8] = b"prof.dump\0";
+
+/// Dumps a memory profile to a file.
+///
+/// If `path` is `None`, the file is named following the pattern
+/// `<prefix>.<pid>.<seq>.m<mseq>.heap`, where `<prefix>` is controlled by the `prof_prefix` option.
+///
+/// This corresponds to `prof.dump` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use std::path::Path;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Err(e) = jemalloc_ctl::prof::dump(Some(Path::new("/tmp/app.heap"))) {
+///         println!("unable to dump profile: {}", e);
+///     }
+/// }
+/// ```
+pub fn dump(path: Option<&Path>) -> Result<()> {
+    Dump::new()?.dump(path)
+}
+
+/// A type providing access to memory profile dumps.
+///
+/// This corresponds to `prof.dump` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::prof::Dump;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let dump = Dump::new().unwrap();
+///
+///     if let Err(e) = dump.dump(None) {
+///         println!("unable to dump profile: {}", e);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Dump(Mib<*const c_char, [usize; 2]>);
+
+impl Dump {
+    /// Returns a new `Dump`.
+    pub fn new() -> Result<Dump> {
+        unsafe { Mib::new(DUMP).map(Dump) }
+    }
+
+    /// Dumps a memory profile to a file.
+    pub fn dump(&self, path: Option<&Path>) -> Result<()> {
+        match path {
+            Some(path) => {
+                let path = c_str(path, &|| name_string(DUMP))?;
+                self.0.write(path.as_ptr())
+            }
+            None => self.0.write(ptr::null()),
+        }
+    }
+}
+
+const GDUMP: &[u8] = b"prof.gdump\0";
+
+/// Determines if a memory profile is dumped every time the total virtual memory exceeds its
+/// previous maximum.
+///
+/// This corresponds to `prof.gdump` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(gdump) = jemalloc_ctl::prof::gdump() {
+///         println!("gdump: {}", gdump);
+///     }
+/// }
+/// ```
+pub fn gdump() -> Result<bool> {
+    unsafe { get(GDUMP) }
+}
+
+/// Enables or disables dumping a memory profile every time the total virtual memory exceeds its
+/// previous maximum.
+///
+/// This corresponds to `prof.gdump` in jemalloc's API.
+pub fn set_gdump(gdump: bool) -> Result<()> {
+    unsafe { set(GDUMP, gdump) }
+}
+
+/// A type providing access to the dumping of memory profiles when the total virtual memory
+/// exceeds its previous maximum.
+///
+/// This corresponds to `prof.gdump` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::prof::Gdump;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let gdump = Gdump::new().unwrap();
+///
+///     if gdump.set(true).is_ok() {
+///         assert!(gdump.get().unwrap());
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Gdump(Mib<bool, [usize; 2]>);
+
+impl Gdump {
+    /// Returns a new `Gdump`.
+    pub fn new() -> Result<Gdump> {
+        unsafe { Mib::new(GDUMP).map(Gdump) }
+    }
+
+    /// Determines if a memory profile is dumped every time the total virtual memory exceeds its
+    /// previous maximum.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+
+    /// Enables or disables dumping a memory profile every time the total virtual memory exceeds
+    /// its previous maximum.
+    pub fn set(&self, gdump: bool) -> Result<()> {
+        self.0.write(gdump)
+    }
+}
+
+const THREAD_ACTIVE_INIT: &[u8] = b"prof.thread_active_init\0";
+
+/// Determines if sampling is initially active for newly created threads.
+///
+/// This corresponds to `prof.thread_active_init` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(thread_active_init) = jemalloc_ctl::prof::thread_active_init() {
+///         println!("thread active init: {}", thread_active_init);
+///     }
+/// }
+/// ```
+pub fn thread_active_init() -> Result<bool> {
+    unsafe { get(THREAD_ACTIVE_INIT) }
+}
+
+/// Sets whether sampling is initially active for newly created threads.
+///
+/// This corresponds to `prof.thread_active_init` in jemalloc's API.
+pub fn set_thread_active_init(thread_active_init: bool) -> Result<()> {
+    unsafe { set(THREAD_ACTIVE_INIT, thread_active_init) }
+}
+
+/// A type providing access to whether sampling is initially active for newly created threads.
+///
+/// This corresponds to `prof.thread_active_init` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::prof::ThreadActiveInit;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let thread_active_init = ThreadActiveInit::new().unwrap();
+///
+///     if thread_active_init.set(false).is_ok() {
+///         assert!(!thread_active_init.get().unwrap());
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ThreadActiveInit(Mib<bool, [usize; 2]>);
+
+impl ThreadActiveInit {
+    /// Returns a new `ThreadActiveInit`.
+    pub fn new() -> Result<ThreadActiveInit> {
+        unsafe { Mib::new(THREAD_ACTIVE_INIT).map(ThreadActiveInit) }
+    }
+
+    /// Determines if sampling is initially active for newly created threads.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+
+    /// Sets whether sampling is initially active for newly created threads.
+    pub fn set(&self, thread_active_init: bool) -> Result<()> {
+        self.0.write(thread_active_init)
+    }
+}
+
+const RESET: &[u8] = b"prof.reset\0";
+
+/// Resets all memory profile statistics.
+///
+/// If `lg_sample` is provided, it replaces the sample rate (see [`lg_sample`]).
+///
+/// This corresponds to `prof.reset` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     // sample an allocation every 2^20 bytes on average
+///     if let Err(e) = jemalloc_ctl::prof::reset(Some(20)) {
+///         println!("unable to reset profile: {}", e);
+///     }
+/// }
+/// ```
+///
+/// [`lg_sample`]: fn.lg_sample.html
+pub fn reset(lg_sample: Option<usize>) -> Result<()> {
+    unsafe {
+        match lg_sample {
+            Some(lg_sample) => set(RESET, lg_sample),
+            None => run(RESET),
+        }
+    }
+}
+
+/// A type providing access to the reset of memory profile statistics.
+///
+/// This corresponds to `prof.reset` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::prof::Reset;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let reset = Reset::new().unwrap();
+///
+///     if let Err(e) = reset.reset(None) {
+///         println!("unable to reset profile: {}", e);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Reset(Mib<usize, [usize; 2]>);
+
+impl Reset {
+    /// Returns a new `Reset`.
+    pub fn new() -> Result<Reset> {
+        unsafe { Mib::new(RESET).map(Reset) }
+    }
+
+    /// Resets all memory profile statistics, optionally replacing the sample rate.
+    pub fn reset(&self, lg_sample: Option<usize>) -> Result<()> {
+        match lg_sample {
+            Some(lg_sample) => self.0.write(lg_sample),
+            None => unsafe { run_mib(self.0.as_slice(), &|| name_string(RESET)) },
+        }
+    }
+}
+
+const LG_SAMPLE: &[u8] = b"prof.lg_sample\0";
+
+/// Returns the base 2 logarithm of the average interval in bytes between allocation samples.
+///
+/// This corresponds to `prof.lg_sample` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(lg_sample) = jemalloc_ctl::prof::lg_sample() {
+///         println!("sampling every {} bytes", 1u64 << lg_sample);
+///     }
+/// }
+/// ```
+pub fn lg_sample() -> Result<usize> {
+    unsafe { get(LG_SAMPLE) }
+}
+
+/// A type providing access to the base 2 logarithm of the average interval in bytes between
+/// allocation samples.
+///
+/// This corresponds to `prof.lg_sample` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::prof::LgSample;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let lg_sample = LgSample::new().unwrap();
+///
+///     if let Ok(lg_sample) = lg_sample.get() {
+///         println!("sampling every {} bytes", 1u64 << lg_sample);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct LgSample(Mib<usize, [usize; 2]>);
+
+impl LgSample {
+    /// Returns a new `LgSample`.
+    pub fn new() -> Result<LgSample> {
+        unsafe { Mib::new(LG_SAMPLE).map(LgSample) }
+    }
+
+    /// Returns the base 2 logarithm of the average interval in bytes between allocation samples.
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
+    }
+}
+
+const INTERVAL: &[u8] = b"prof.interval\0";
+
+/// Returns the average number of bytes allocated between interval-triggered profile dumps.
+///
+/// A value of 0 indicates that interval-triggered dumps are disabled.
+///
+/// This corresponds to `prof.interval` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(interval) = jemalloc_ctl::prof::interval() {
+///         println!("dumping every {} bytes", interval);
+///     }
+/// }
+/// ```
+pub fn interval() -> Result<u64> {
+    unsafe { get(INTERVAL) }
+}
+
+/// A type providing access to the average number of bytes allocated between interval-triggered
+/// profile dumps.
+///
+/// This corresponds to `prof.interval` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::prof::Interval;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let interval = Interval::new().unwrap();
+///
+///     if let Ok(interval) = interval.get() {
+///         println!("dumping every {} bytes", interval);
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Interval(Mib<u64, [usize; 2]>);
+
+impl Interval {
+    /// Returns a new `Interval`.
+    pub fn new() -> Result<Interval> {
+        unsafe { Mib::new(INTERVAL).map(Interval) }
+    }
+
+    /// Returns the average number of bytes allocated between interval-triggered profile dumps.
+    pub fn get(&self) -> Result<u64> {
+        self.0.read()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use Error;
+
+    #[test]
+    fn not_configured() {
+        if ::config::prof().unwrap() {
+            return;
+        }
+
+        match active() {
+            Err(Error::NotConfigured { ref name }) => assert_eq!(name, "prof.active"),
+            r => panic!("unexpected result {:?}", r),
+        }
+        match dump(None) {
+            Err(Error::NotConfigured { .. }) => {}
+            r => panic!("unexpected result {:?}", r),
+        }
+        match reset(Some(19)) {
+            Err(Error::NotConfigured { .. }) => {}
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+
+    #[test]
+    fn dump_interior_nul() {
+        match dump(Some(Path::new("foo\0bar"))) {
+            Err(Error::InvalidValue { ref name }) => assert_eq!(name, "prof.dump"),
+            r => panic!("unexpected result {:?}", r),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Raw, name-based access to `mallctl` operations.
+//!
+//! These functions accept any operation name, and are useful for operations this crate doesn't
+//! otherwise expose. Names must be NUL-terminated byte strings such as `b"stats.allocated\0"`; a
+//! `&CStr` can be passed via `CStr::to_bytes_with_nul`.
+//!
+//! The names are parsed on every call. See [`Mib`] for a type which performs the lookup once.
+//!
+//! [`Mib`]: ../struct.Mib.html
+
+use {get, get_set, get_str, set, set_str, Result, ToCStr};
+
+/// Returns the value of the operation with the specified name.
+///
+/// An error is returned if the name is not NUL-terminated, or if jemalloc's value for the
+/// operation is not the same size as `T`. jemalloc usually rejects a mismatched size itself, which
+/// is reported as [`Error::InvalidValue`]; [`Error::SizeMismatch`] is only returned if jemalloc
+/// instead writes a shorter value than requested.
+///
+/// [`Error::InvalidValue`]: ../enum.Error.html#variant.InvalidValue
+/// [`Error::SizeMismatch`]: ../enum.Error.html#variant.SizeMismatch
+///
+/// # Safety
+///
+/// `T` must be the type jemalloc uses for the operation's value.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use std::ffi::CStr;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let name = CStr::from_bytes_with_nul(b"arenas.page\0").unwrap();
+///     let page = unsafe { jemalloc_ctl::raw::read::<usize>(name.to_bytes_with_nul()).unwrap() };
+///     println!("page size: {}", page);
+/// }
+/// ```
+pub unsafe fn read<T>(name: &[u8]) -> Result<T>
+where
+    T: Copy,
+{
+    get(name)
+}
+
+/// Sets the value of the operation with the specified name.
+///
+/// An error is returned if the name is not NUL-terminated.
+///
+/// # Safety
+///
+/// `T` must be the type jemalloc uses for the operation's value.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     unsafe {
+///         jemalloc_ctl::raw::write(b"background_thread\0", true).unwrap();
+///         assert!(jemalloc_ctl::raw::read::<bool>(b"background_thread\0").unwrap());
+///     }
+/// }
+/// ```
+pub unsafe fn write<T>(name: &[u8], value: T) -> Result<()>
+where
+    T: Copy,
+{
+    set(name, value)
+}
+
+/// Sets the value of the operation with the specified name, returning its previous value.
+///
+/// An error is returned if the name is not NUL-terminated, or if jemalloc's value for the
+/// operation is not the same size as `T`. jemalloc usually rejects a mismatched size itself, which
+/// is reported as [`Error::InvalidValue`]; [`Error::SizeMismatch`] is only returned if jemalloc
+/// instead writes a shorter value than requested.
+///
+/// [`Error::InvalidValue`]: ../enum.Error.html#variant.InvalidValue
+/// [`Error::SizeMismatch`]: ../enum.Error.html#variant.SizeMismatch
+///
+/// # Safety
+///
+/// `T` must be the type jemalloc uses for the operation's value.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let a = unsafe { jemalloc_ctl::raw::update(b"epoch\0", 1u64).unwrap() };
+///     let b = unsafe { jemalloc_ctl::raw::update(b"epoch\0", 1u64).unwrap() };
+///     assert_eq!(a + 1, b);
+/// }
+/// ```
+pub unsafe fn update<T>(name: &[u8], value: T) -> Result<T>
+where
+    T: Copy,
+{
+    get_set(name, value)
+}
+
+/// Returns the value of the string-valued operation with the specified name.
+///
+/// An error is returned if the name is not NUL-terminated, or if the string is not valid UTF-8.
+///
+/// # Safety
+///
+/// The operation's value must be a `const char *` pointing to a string which lives for the
+/// remainder of the program.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let dss = unsafe { jemalloc_ctl::raw::read_str(b"opt.dss\0").unwrap() };
+///     println!("dss priority: {}", dss);
+/// }
+/// ```
+pub unsafe fn read_str(name: &[u8]) -> Result<&'static str> {
+    get_str(name)
+}
+
+/// Sets the value of the string-valued operation with the specified name.
+///
+/// The value can be any [`ToCStr`] type, such as a `&str`, `&CStr` or `&Path`. An error is returned
+/// if the name is not NUL-terminated, or if the value contains an interior NUL byte.
+///
+/// # Safety
+///
+/// The operation's value must be a `const char *`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     unsafe {
+///         jemalloc_ctl::raw::write_str(b"arena.0.dss\0", "secondary").unwrap();
+///         assert_eq!(jemalloc_ctl::raw::read_str(b"arena.0.dss\0").unwrap(), "secondary");
+///     }
+/// }
+/// ```
+///
+/// [`ToCStr`]: ../trait.ToCStr.html
+pub unsafe fn write_str<S>(name: &[u8], value: &S) -> Result<()>
+where
+    S: ToCStr + ?Sized,
+{
+    set_str(name, value)
+}
@@ -0,0 +1,1939 @@
+//! Per-arena allocator statistics.
+//!
+//! Each statistic is indexed by an arena index, which ranges from 0 up to (but not including) the
+//! value returned by [`arenas::narenas`]. The [`ALL`] and [`DESTROYED`] pseudo-indices can be used
+//! to access statistics merged across all arenas and across all destroyed arenas respectively.
+//! Statistics for arenas which have not been initialized are not available, and a
+//! [`Error::UnknownName`] error is returned for them.
+//!
+//! Like the global statistics, these are cached and only refreshed when the epoch is advanced. See
+//! the [`Epoch`] type for more information.
+//!
+//! The functions in this module perform a MIB lookup on every call. The types perform the lookup
+//! once on construction, and can then be used to efficiently read the statistic for any number of
+//! arenas.
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate jemallocator;
+//! extern crate jemalloc_ctl;
+//!
+//! use jemalloc_ctl::arenas;
+//! use jemalloc_ctl::stats::arenas::PActive;
+//!
+//! #[global_allocator]
+//! static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+//!
+//! fn main() {
+//!     jemalloc_ctl::epoch().unwrap();
+//!
+//!     let pactive = PActive::new().unwrap();
+//!     for arena in 0..arenas::narenas().unwrap() {
+//!         if let Ok(pages) = pactive.get(arena) {
+//!             println!("arena {}: {} active pages", arena, pages);
+//!         }
+//!     }
+//!     println!("total: {} active pages", pactive.get(arenas::ALL).unwrap());
+//! }
+//! ```
+//!
+//! [`arenas::narenas`]: ../../arenas/fn.narenas.html
+//! [`ALL`]: ../../arenas/constant.ALL.html
+//! [`DESTROYED`]: ../../arenas/constant.DESTROYED.html
+//! [`Error::UnknownName`]: ../../enum.Error.html#variant.UnknownName
+//! [`Epoch`]: ../../struct.Epoch.html
+use std::os::raw::c_uint;
+
+use {get, optional, Mib, Result};
+
+const NTHREADS: &[u8] = b"stats.arenas.0.nthreads\0";
+
+/// Returns the number of threads currently assigned to the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.nthreads` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::nthreads(0).unwrap();
+///     println!("arena 0: {} threads", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn nthreads(arena: c_uint) -> Result<c_uint> {
+    NThreads::new()?.get(arena)
+}
+
+/// A type providing access to the number of threads currently assigned to the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.nthreads` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::NThreads;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let nthreads = NThreads::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} threads", nthreads.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct NThreads(Mib<c_uint, [usize; 4]>);
+
+impl NThreads {
+    /// Returns a new `NThreads`.
+    pub fn new() -> Result<NThreads> {
+        unsafe { Mib::new(NTHREADS).map(NThreads) }
+    }
+
+    /// Returns the number of threads currently assigned to the arena.
+    pub fn get(&self, arena: c_uint) -> Result<c_uint> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const UPTIME: &[u8] = b"stats.arenas.0.uptime\0";
+
+/// Returns the time in nanoseconds since the arena was created.
+///
+/// For arena 0 and [`ALL`], this is the time since jemalloc was initialized.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.uptime` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::uptime(0).unwrap();
+///     println!("arena 0: {} nanoseconds of uptime", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+/// [`ALL`]: ../../arenas/constant.ALL.html
+pub fn uptime(arena: c_uint) -> Result<u64> {
+    Uptime::new()?.get(arena)
+}
+
+/// A type providing access to the time in nanoseconds since the arena was created.
+///
+/// For arena 0 and [`ALL`], this is the time since jemalloc was initialized.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.uptime` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::Uptime;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let uptime = Uptime::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} nanoseconds of uptime", uptime.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+/// [`ALL`]: ../../arenas/constant.ALL.html
+#[derive(Copy, Clone)]
+pub struct Uptime(Mib<u64, [usize; 4]>);
+
+impl Uptime {
+    /// Returns a new `Uptime`.
+    pub fn new() -> Result<Uptime> {
+        unsafe { Mib::new(UPTIME).map(Uptime) }
+    }
+
+    /// Returns the time in nanoseconds since the arena was created.
+    pub fn get(&self, arena: c_uint) -> Result<u64> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const PACTIVE: &[u8] = b"stats.arenas.0.pactive\0";
+
+/// Returns the number of pages in active extents.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.pactive` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::pactive(0).unwrap();
+///     println!("arena 0: {} active pages", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn pactive(arena: c_uint) -> Result<usize> {
+    PActive::new()?.get(arena)
+}
+
+/// A type providing access to the number of pages in active extents.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.pactive` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::PActive;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let pactive = PActive::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} active pages", pactive.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct PActive(Mib<usize, [usize; 4]>);
+
+impl PActive {
+    /// Returns a new `PActive`.
+    pub fn new() -> Result<PActive> {
+        unsafe { Mib::new(PACTIVE).map(PActive) }
+    }
+
+    /// Returns the number of pages in active extents.
+    pub fn get(&self, arena: c_uint) -> Result<usize> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const PDIRTY: &[u8] = b"stats.arenas.0.pdirty\0";
+
+/// Returns the number of pages within unused extents which are potentially dirty, and for which
+/// `madvise` or similar has not been called.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.pdirty` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::pdirty(0).unwrap();
+///     println!("arena 0: {} dirty pages", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn pdirty(arena: c_uint) -> Result<usize> {
+    PDirty::new()?.get(arena)
+}
+
+/// A type providing access to the number of pages within unused extents which are potentially
+/// dirty, and for which `madvise` or similar has not been called.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.pdirty` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::PDirty;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let pdirty = PDirty::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} dirty pages", pdirty.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct PDirty(Mib<usize, [usize; 4]>);
+
+impl PDirty {
+    /// Returns a new `PDirty`.
+    pub fn new() -> Result<PDirty> {
+        unsafe { Mib::new(PDIRTY).map(PDirty) }
+    }
+
+    /// Returns the number of pages within unused extents which are potentially dirty, and for which
+    /// `madvise` or similar has not been called.
+    pub fn get(&self, arena: c_uint) -> Result<usize> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const PMUZZY: &[u8] = b"stats.arenas.0.pmuzzy\0";
+
+/// Returns the number of pages within unused extents which are muzzy.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.pmuzzy` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::pmuzzy(0).unwrap();
+///     println!("arena 0: {} muzzy pages", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn pmuzzy(arena: c_uint) -> Result<usize> {
+    PMuzzy::new()?.get(arena)
+}
+
+/// A type providing access to the number of pages within unused extents which are muzzy.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.pmuzzy` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::PMuzzy;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let pmuzzy = PMuzzy::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} muzzy pages", pmuzzy.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct PMuzzy(Mib<usize, [usize; 4]>);
+
+impl PMuzzy {
+    /// Returns a new `PMuzzy`.
+    pub fn new() -> Result<PMuzzy> {
+        unsafe { Mib::new(PMUZZY).map(PMuzzy) }
+    }
+
+    /// Returns the number of pages within unused extents which are muzzy.
+    pub fn get(&self, arena: c_uint) -> Result<usize> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const MAPPED: &[u8] = b"stats.arenas.0.mapped\0";
+
+/// Returns the number of bytes mapped by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.mapped` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::mapped(0).unwrap();
+///     println!("arena 0: {} bytes mapped", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn mapped(arena: c_uint) -> Result<usize> {
+    Mapped::new()?.get(arena)
+}
+
+/// A type providing access to the number of bytes mapped by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.mapped` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::Mapped;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let mapped = Mapped::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} bytes mapped", mapped.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct Mapped(Mib<usize, [usize; 4]>);
+
+impl Mapped {
+    /// Returns a new `Mapped`.
+    pub fn new() -> Result<Mapped> {
+        unsafe { Mib::new(MAPPED).map(Mapped) }
+    }
+
+    /// Returns the number of bytes mapped by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<usize> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const RETAINED: &[u8] = b"stats.arenas.0.retained\0";
+
+/// Returns the number of bytes retained by the arena.
+///
+/// Retained virtual memory is not backed by physical memory, and is available for reuse by the
+/// arena. See [`stats::retained`] for more information.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.retained` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::retained(0).unwrap();
+///     println!("arena 0: {} bytes retained", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+/// [`stats::retained`]: ../fn.retained.html
+pub fn retained(arena: c_uint) -> Result<usize> {
+    Retained::new()?.get(arena)
+}
+
+/// A type providing access to the number of bytes retained by the arena.
+///
+/// Retained virtual memory is not backed by physical memory, and is available for reuse by the
+/// arena. See [`stats::retained`] for more information.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.retained` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::Retained;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let retained = Retained::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} bytes retained", retained.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+/// [`stats::retained`]: ../fn.retained.html
+#[derive(Copy, Clone)]
+pub struct Retained(Mib<usize, [usize; 4]>);
+
+impl Retained {
+    /// Returns a new `Retained`.
+    pub fn new() -> Result<Retained> {
+        unsafe { Mib::new(RETAINED).map(Retained) }
+    }
+
+    /// Returns the number of bytes retained by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<usize> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const BASE: &[u8] = b"stats.arenas.0.base\0";
+
+/// Returns the number of bytes dedicated to bootstrap-sensitive allocator metadata.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.base` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::base(0).unwrap();
+///     println!("arena 0: {} bytes of base metadata", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn base(arena: c_uint) -> Result<usize> {
+    Base::new()?.get(arena)
+}
+
+/// A type providing access to the number of bytes dedicated to bootstrap-sensitive allocator
+/// metadata.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.base` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::Base;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let base = Base::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} bytes of base metadata", base.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct Base(Mib<usize, [usize; 4]>);
+
+impl Base {
+    /// Returns a new `Base`.
+    pub fn new() -> Result<Base> {
+        unsafe { Mib::new(BASE).map(Base) }
+    }
+
+    /// Returns the number of bytes dedicated to bootstrap-sensitive allocator metadata.
+    pub fn get(&self, arena: c_uint) -> Result<usize> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const INTERNAL: &[u8] = b"stats.arenas.0.internal\0";
+
+/// Returns the number of bytes dedicated to internal allocations.
+///
+/// Internal allocations differ from application-originated allocations in that they are for
+/// internal use, and that they are omitted from heap profiles.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.internal` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::internal(0).unwrap();
+///     println!("arena 0: {} bytes of internal allocations", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn internal(arena: c_uint) -> Result<usize> {
+    Internal::new()?.get(arena)
+}
+
+/// A type providing access to the number of bytes dedicated to internal allocations.
+///
+/// Internal allocations differ from application-originated allocations in that they are for
+/// internal use, and that they are omitted from heap profiles.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.internal` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::Internal;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let internal = Internal::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} bytes of internal allocations", internal.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct Internal(Mib<usize, [usize; 4]>);
+
+impl Internal {
+    /// Returns a new `Internal`.
+    pub fn new() -> Result<Internal> {
+        unsafe { Mib::new(INTERNAL).map(Internal) }
+    }
+
+    /// Returns the number of bytes dedicated to internal allocations.
+    pub fn get(&self, arena: c_uint) -> Result<usize> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const RESIDENT: &[u8] = b"stats.arenas.0.resident\0";
+
+/// Returns the number of bytes in physically resident data pages mapped by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.resident` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::resident(0).unwrap();
+///     println!("arena 0: {} resident bytes", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn resident(arena: c_uint) -> Result<usize> {
+    Resident::new()?.get(arena)
+}
+
+/// A type providing access to the number of bytes in physically resident data pages mapped by the
+/// arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.resident` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::Resident;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let resident = Resident::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} resident bytes", resident.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct Resident(Mib<usize, [usize; 4]>);
+
+impl Resident {
+    /// Returns a new `Resident`.
+    pub fn new() -> Result<Resident> {
+        unsafe { Mib::new(RESIDENT).map(Resident) }
+    }
+
+    /// Returns the number of bytes in physically resident data pages mapped by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<usize> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const DIRTY_NPURGE: &[u8] = b"stats.arenas.0.dirty_npurge\0";
+
+/// Returns the number of dirty page purge sweeps performed by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.dirty_npurge` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::dirty_npurge(0).unwrap();
+///     println!("arena 0: {} dirty purge sweeps", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn dirty_npurge(arena: c_uint) -> Result<u64> {
+    DirtyNPurge::new()?.get(arena)
+}
+
+/// A type providing access to the number of dirty page purge sweeps performed by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.dirty_npurge` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::DirtyNPurge;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let dirty_npurge = DirtyNPurge::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} dirty purge sweeps", dirty_npurge.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct DirtyNPurge(Mib<u64, [usize; 4]>);
+
+impl DirtyNPurge {
+    /// Returns a new `DirtyNPurge`.
+    pub fn new() -> Result<DirtyNPurge> {
+        unsafe { Mib::new(DIRTY_NPURGE).map(DirtyNPurge) }
+    }
+
+    /// Returns the number of dirty page purge sweeps performed by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<u64> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const DIRTY_NMADVISE: &[u8] = b"stats.arenas.0.dirty_nmadvise\0";
+
+/// Returns the number of `madvise` or similar calls made by the arena to purge dirty pages.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.dirty_nmadvise` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::dirty_nmadvise(0).unwrap();
+///     println!("arena 0: {} dirty purge calls", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn dirty_nmadvise(arena: c_uint) -> Result<u64> {
+    DirtyNMadvise::new()?.get(arena)
+}
+
+/// A type providing access to the number of `madvise` or similar calls made by the arena to purge
+/// dirty pages.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.dirty_nmadvise` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::DirtyNMadvise;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let dirty_nmadvise = DirtyNMadvise::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} dirty purge calls", dirty_nmadvise.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct DirtyNMadvise(Mib<u64, [usize; 4]>);
+
+impl DirtyNMadvise {
+    /// Returns a new `DirtyNMadvise`.
+    pub fn new() -> Result<DirtyNMadvise> {
+        unsafe { Mib::new(DIRTY_NMADVISE).map(DirtyNMadvise) }
+    }
+
+    /// Returns the number of `madvise` or similar calls made by the arena to purge dirty pages.
+    pub fn get(&self, arena: c_uint) -> Result<u64> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const DIRTY_PURGED: &[u8] = b"stats.arenas.0.dirty_purged\0";
+
+/// Returns the number of dirty pages purged by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.dirty_purged` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::dirty_purged(0).unwrap();
+///     println!("arena 0: {} dirty pages purged", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn dirty_purged(arena: c_uint) -> Result<u64> {
+    DirtyPurged::new()?.get(arena)
+}
+
+/// A type providing access to the number of dirty pages purged by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.dirty_purged` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::DirtyPurged;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let dirty_purged = DirtyPurged::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} dirty pages purged", dirty_purged.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct DirtyPurged(Mib<u64, [usize; 4]>);
+
+impl DirtyPurged {
+    /// Returns a new `DirtyPurged`.
+    pub fn new() -> Result<DirtyPurged> {
+        unsafe { Mib::new(DIRTY_PURGED).map(DirtyPurged) }
+    }
+
+    /// Returns the number of dirty pages purged by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<u64> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const MUZZY_NPURGE: &[u8] = b"stats.arenas.0.muzzy_npurge\0";
+
+/// Returns the number of muzzy page purge sweeps performed by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.muzzy_npurge` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::muzzy_npurge(0).unwrap();
+///     println!("arena 0: {} muzzy purge sweeps", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn muzzy_npurge(arena: c_uint) -> Result<u64> {
+    MuzzyNPurge::new()?.get(arena)
+}
+
+/// A type providing access to the number of muzzy page purge sweeps performed by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.muzzy_npurge` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::MuzzyNPurge;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let muzzy_npurge = MuzzyNPurge::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} muzzy purge sweeps", muzzy_npurge.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct MuzzyNPurge(Mib<u64, [usize; 4]>);
+
+impl MuzzyNPurge {
+    /// Returns a new `MuzzyNPurge`.
+    pub fn new() -> Result<MuzzyNPurge> {
+        unsafe { Mib::new(MUZZY_NPURGE).map(MuzzyNPurge) }
+    }
+
+    /// Returns the number of muzzy page purge sweeps performed by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<u64> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const MUZZY_NMADVISE: &[u8] = b"stats.arenas.0.muzzy_nmadvise\0";
+
+/// Returns the number of `madvise` or similar calls made by the arena to purge muzzy pages.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.muzzy_nmadvise` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::muzzy_nmadvise(0).unwrap();
+///     println!("arena 0: {} muzzy purge calls", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn muzzy_nmadvise(arena: c_uint) -> Result<u64> {
+    MuzzyNMadvise::new()?.get(arena)
+}
+
+/// A type providing access to the number of `madvise` or similar calls made by the arena to purge
+/// muzzy pages.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.muzzy_nmadvise` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::MuzzyNMadvise;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let muzzy_nmadvise = MuzzyNMadvise::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} muzzy purge calls", muzzy_nmadvise.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct MuzzyNMadvise(Mib<u64, [usize; 4]>);
+
+impl MuzzyNMadvise {
+    /// Returns a new `MuzzyNMadvise`.
+    pub fn new() -> Result<MuzzyNMadvise> {
+        unsafe { Mib::new(MUZZY_NMADVISE).map(MuzzyNMadvise) }
+    }
+
+    /// Returns the number of `madvise` or similar calls made by the arena to purge muzzy pages.
+    pub fn get(&self, arena: c_uint) -> Result<u64> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+const MUZZY_PURGED: &[u8] = b"stats.arenas.0.muzzy_purged\0";
+
+/// Returns the number of muzzy pages purged by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`epoch`]
+/// function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.muzzy_purged` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let value = jemalloc_ctl::stats::arenas::muzzy_purged(0).unwrap();
+///     println!("arena 0: {} muzzy pages purged", value);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn muzzy_purged(arena: c_uint) -> Result<u64> {
+    MuzzyPurged::new()?.get(arena)
+}
+
+/// A type providing access to the number of muzzy pages purged by the arena.
+///
+/// This statistic is cached, and is only refreshed when the epoch is advanced. See the [`Epoch`]
+/// type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.muzzy_purged` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::MuzzyPurged;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let muzzy_purged = MuzzyPurged::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     println!("arena 0: {} muzzy pages purged", muzzy_purged.get(0).unwrap());
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct MuzzyPurged(Mib<u64, [usize; 4]>);
+
+impl MuzzyPurged {
+    /// Returns a new `MuzzyPurged`.
+    pub fn new() -> Result<MuzzyPurged> {
+        unsafe { Mib::new(MUZZY_PURGED).map(MuzzyPurged) }
+    }
+
+    /// Returns the number of muzzy pages purged by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<u64> {
+        self.0.with_index(0, arena as usize).read()
+    }
+}
+
+/// Statistics about the allocations of one class of sizes made by an arena.
+///
+/// Small allocations are those which are served by slabs of same-sized regions, and large
+/// allocations are those which are backed by their own extents.
+#[derive(Debug, Copy, Clone)]
+pub struct ClassStats {
+    /// The number of bytes currently allocated.
+    pub allocated: usize,
+
+    /// The cumulative number of times an allocation was served by the arena.
+    pub nmalloc: u64,
+
+    /// The cumulative number of times an allocation was returned to the arena.
+    pub ndalloc: u64,
+
+    /// The cumulative number of allocation requests, including those satisfied by thread caches.
+    pub nrequests: u64,
+
+    /// The cumulative number of thread cache fills.
+    ///
+    /// This is `None` if the version of jemalloc in use does not track it.
+    pub nfills: Option<u64>,
+
+    /// The cumulative number of thread cache flushes.
+    ///
+    /// This is `None` if the version of jemalloc in use does not track it.
+    pub nflushes: Option<u64>,
+}
+
+#[derive(Copy, Clone)]
+struct ClassMibs {
+    allocated: Mib<usize, [usize; 5]>,
+    nmalloc: Mib<u64, [usize; 5]>,
+    ndalloc: Mib<u64, [usize; 5]>,
+    nrequests: Mib<u64, [usize; 5]>,
+    nfills: Option<Mib<u64, [usize; 5]>>,
+    nflushes: Option<Mib<u64, [usize; 5]>>,
+}
+
+impl ClassMibs {
+    unsafe fn new(names: &[&'static [u8]; 6]) -> Result<ClassMibs> {
+        Ok(ClassMibs {
+            allocated: Mib::new(names[0])?,
+            nmalloc: Mib::new(names[1])?,
+            ndalloc: Mib::new(names[2])?,
+            nrequests: Mib::new(names[3])?,
+            nfills: optional(Mib::new(names[4]))?,
+            nflushes: optional(Mib::new(names[5]))?,
+        })
+    }
+
+    fn get(&self, arena: c_uint) -> Result<ClassStats> {
+        let arena = arena as usize;
+        Ok(ClassStats {
+            allocated: self.allocated.with_index(0, arena).read()?,
+            nmalloc: self.nmalloc.with_index(0, arena).read()?,
+            ndalloc: self.ndalloc.with_index(0, arena).read()?,
+            nrequests: self.nrequests.with_index(0, arena).read()?,
+            nfills: match self.nfills {
+                Some(mib) => Some(mib.with_index(0, arena).read()?),
+                None => None,
+            },
+            nflushes: match self.nflushes {
+                Some(mib) => Some(mib.with_index(0, arena).read()?),
+                None => None,
+            },
+        })
+    }
+}
+
+const SMALL: [&[u8]; 6] = [
+    b"stats.arenas.0.small.allocated\0",
+    b"stats.arenas.0.small.nmalloc\0",
+    b"stats.arenas.0.small.ndalloc\0",
+    b"stats.arenas.0.small.nrequests\0",
+    b"stats.arenas.0.small.nfills\0",
+    b"stats.arenas.0.small.nflushes\0",
+];
+
+/// Returns statistics about the small allocations made by the arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`epoch`] function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.small.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let _buf = vec![0u8; 16];
+///     jemalloc_ctl::epoch().unwrap();
+///
+///     let small = jemalloc_ctl::stats::arenas::small(jemalloc_ctl::arenas::ALL).unwrap();
+///     assert!(small.allocated > 0);
+///     println!("{} bytes in small allocations", small.allocated);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn small(arena: c_uint) -> Result<ClassStats> {
+    Small::new()?.get(arena)
+}
+
+/// A type providing access to statistics about the small allocations made by an arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`Epoch`] type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.small.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::Small;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let small = Small::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     let stats = small.get(0).unwrap();
+///     println!("arena 0: {} small allocations", stats.nmalloc);
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct Small(ClassMibs);
+
+impl Small {
+    /// Returns a new `Small`.
+    pub fn new() -> Result<Small> {
+        unsafe { ClassMibs::new(&SMALL).map(Small) }
+    }
+
+    /// Returns statistics about the small allocations made by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<ClassStats> {
+        self.0.get(arena)
+    }
+}
+
+const LARGE: [&[u8]; 6] = [
+    b"stats.arenas.0.large.allocated\0",
+    b"stats.arenas.0.large.nmalloc\0",
+    b"stats.arenas.0.large.ndalloc\0",
+    b"stats.arenas.0.large.nrequests\0",
+    b"stats.arenas.0.large.nfills\0",
+    b"stats.arenas.0.large.nflushes\0",
+];
+
+/// Returns statistics about the large allocations made by the arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`epoch`] function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.large.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let _buf = vec![0u8; 1024 * 1024];
+///     jemalloc_ctl::epoch().unwrap();
+///
+///     let large = jemalloc_ctl::stats::arenas::large(jemalloc_ctl::arenas::ALL).unwrap();
+///     assert!(large.allocated >= 1024 * 1024);
+///     println!("{} bytes in large allocations", large.allocated);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn large(arena: c_uint) -> Result<ClassStats> {
+    Large::new()?.get(arena)
+}
+
+/// A type providing access to statistics about the large allocations made by an arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`Epoch`] type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.large.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::Large;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let large = Large::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     let stats = large.get(0).unwrap();
+///     println!("arena 0: {} large allocations", stats.nmalloc);
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct Large(ClassMibs);
+
+impl Large {
+    /// Returns a new `Large`.
+    pub fn new() -> Result<Large> {
+        unsafe { ClassMibs::new(&LARGE).map(Large) }
+    }
+
+    /// Returns statistics about the large allocations made by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<ClassStats> {
+        self.0.get(arena)
+    }
+}
+
+const NBINS: &[u8] = b"arenas.nbins\0";
+const BIN_SIZE: &[u8] = b"arenas.bin.0.size\0";
+const BIN_NREGS: &[u8] = b"arenas.bin.0.nregs\0";
+const BIN_SLAB_SIZE: &[u8] = b"arenas.bin.0.slab_size\0";
+const BIN_NMALLOC: &[u8] = b"stats.arenas.0.bins.0.nmalloc\0";
+const BIN_NDALLOC: &[u8] = b"stats.arenas.0.bins.0.ndalloc\0";
+const BIN_NREQUESTS: &[u8] = b"stats.arenas.0.bins.0.nrequests\0";
+const BIN_CURREGS: &[u8] = b"stats.arenas.0.bins.0.curregs\0";
+const BIN_NFILLS: &[u8] = b"stats.arenas.0.bins.0.nfills\0";
+const BIN_NFLUSHES: &[u8] = b"stats.arenas.0.bins.0.nflushes\0";
+const BIN_NSLABS: &[u8] = b"stats.arenas.0.bins.0.nslabs\0";
+const BIN_NRESLABS: &[u8] = b"stats.arenas.0.bins.0.nreslabs\0";
+const BIN_CURSLABS: &[u8] = b"stats.arenas.0.bins.0.curslabs\0";
+const BIN_NONFULL_SLABS: &[u8] = b"stats.arenas.0.bins.0.nonfull_slabs\0";
+
+/// Statistics about the allocations of one bin size class made by an arena.
+///
+/// Each bin serves small allocations of a single size from slabs of same-sized regions.
+#[derive(Debug, Copy, Clone)]
+pub struct BinStats {
+    /// The size of the regions in the bin.
+    pub size: usize,
+
+    /// The number of regions per slab.
+    pub nregs: u32,
+
+    /// The number of bytes per slab.
+    pub slab_size: usize,
+
+    /// The cumulative number of times an allocation was served by the bin.
+    pub nmalloc: u64,
+
+    /// The cumulative number of times an allocation was returned to the bin.
+    pub ndalloc: u64,
+
+    /// The cumulative number of allocation requests, including those satisfied by thread caches.
+    pub nrequests: u64,
+
+    /// The current number of regions in use.
+    pub curregs: usize,
+
+    /// The cumulative number of thread cache fills.
+    pub nfills: u64,
+
+    /// The cumulative number of thread cache flushes.
+    pub nflushes: u64,
+
+    /// The cumulative number of slabs created.
+    pub nslabs: u64,
+
+    /// The cumulative number of times the current slab was replaced with one which was more full.
+    pub nreslabs: u64,
+
+    /// The current number of slabs.
+    pub curslabs: usize,
+
+    /// The current number of slabs which are neither full nor empty.
+    ///
+    /// This is `None` if the version of jemalloc in use does not track it.
+    pub nonfull_slabs: Option<usize>,
+}
+
+/// Returns an iterator over statistics for each bin size class of the arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`epoch`] function for more information.
+///
+/// This combines `arenas.bin.<j>.*` and `stats.arenas.<i>.bins.<j>.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///
+///     for bin in jemalloc_ctl::stats::arenas::bins(0).unwrap() {
+///         let bin = bin.unwrap();
+///         println!("{} byte regions: {} in use", bin.size, bin.curregs);
+///     }
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn bins(arena: c_uint) -> Result<BinIter> {
+    Bins::new().map(|bins| bins.iter(arena))
+}
+
+/// A type providing access to statistics for each bin size class of an arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`Epoch`] type for more information.
+///
+/// This combines `arenas.bin.<j>.*` and `stats.arenas.<i>.bins.<j>.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::Bins;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let bins = Bins::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     let smallest = bins.get(0, 0).unwrap();
+///     println!("{} byte regions: {} allocations", smallest.size, smallest.nmalloc);
+///
+///     let total = bins
+///         .iter(0)
+///         .map(|bin| bin.map(|bin| bin.curregs * bin.size))
+///         .sum::<Result<usize, _>>()
+///         .unwrap();
+///     println!("{} bytes in use across all bins", total);
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct Bins {
+    nbins: c_uint,
+    size: Mib<usize, [usize; 4]>,
+    nregs: Mib<u32, [usize; 4]>,
+    slab_size: Mib<usize, [usize; 4]>,
+    nmalloc: Mib<u64, [usize; 6]>,
+    ndalloc: Mib<u64, [usize; 6]>,
+    nrequests: Mib<u64, [usize; 6]>,
+    curregs: Mib<usize, [usize; 6]>,
+    nfills: Mib<u64, [usize; 6]>,
+    nflushes: Mib<u64, [usize; 6]>,
+    nslabs: Mib<u64, [usize; 6]>,
+    nreslabs: Mib<u64, [usize; 6]>,
+    curslabs: Mib<usize, [usize; 6]>,
+    nonfull_slabs: Option<Mib<usize, [usize; 6]>>,
+}
+
+impl Bins {
+    /// Returns a new `Bins`.
+    pub fn new() -> Result<Bins> {
+        unsafe {
+            Ok(Bins {
+                nbins: get(NBINS)?,
+                size: Mib::new(BIN_SIZE)?,
+                nregs: Mib::new(BIN_NREGS)?,
+                slab_size: Mib::new(BIN_SLAB_SIZE)?,
+                nmalloc: Mib::new(BIN_NMALLOC)?,
+                ndalloc: Mib::new(BIN_NDALLOC)?,
+                nrequests: Mib::new(BIN_NREQUESTS)?,
+                curregs: Mib::new(BIN_CURREGS)?,
+                nfills: Mib::new(BIN_NFILLS)?,
+                nflushes: Mib::new(BIN_NFLUSHES)?,
+                nslabs: Mib::new(BIN_NSLABS)?,
+                nreslabs: Mib::new(BIN_NRESLABS)?,
+                curslabs: Mib::new(BIN_CURSLABS)?,
+                nonfull_slabs: optional(Mib::new(BIN_NONFULL_SLABS))?,
+            })
+        }
+    }
+
+    /// Returns the number of bin size classes.
+    pub fn nbins(&self) -> c_uint {
+        self.nbins
+    }
+
+    /// Returns statistics for a single bin size class of the arena.
+    pub fn get(&self, arena: c_uint, bin: c_uint) -> Result<BinStats> {
+        let arena = arena as usize;
+        let bin = bin as usize;
+        Ok(BinStats {
+            size: self.size.with_index(0, bin).read()?,
+            nregs: self.nregs.with_index(0, bin).read()?,
+            slab_size: self.slab_size.with_index(0, bin).read()?,
+            nmalloc: self.stat(self.nmalloc, arena, bin)?,
+            ndalloc: self.stat(self.ndalloc, arena, bin)?,
+            nrequests: self.stat(self.nrequests, arena, bin)?,
+            curregs: self.stat(self.curregs, arena, bin)?,
+            nfills: self.stat(self.nfills, arena, bin)?,
+            nflushes: self.stat(self.nflushes, arena, bin)?,
+            nslabs: self.stat(self.nslabs, arena, bin)?,
+            nreslabs: self.stat(self.nreslabs, arena, bin)?,
+            curslabs: self.stat(self.curslabs, arena, bin)?,
+            nonfull_slabs: match self.nonfull_slabs {
+                Some(mib) => Some(self.stat(mib, arena, bin)?),
+                None => None,
+            },
+        })
+    }
+
+    /// Returns an iterator over statistics for each bin size class of the arena.
+    pub fn iter(&self, arena: c_uint) -> BinIter {
+        BinIter {
+            bins: *self,
+            arena,
+            next: 0,
+        }
+    }
+
+    fn stat<T>(&self, mib: Mib<T, [usize; 6]>, arena: usize, bin: usize) -> Result<T>
+    where
+        T: Copy,
+    {
+        mib.with_index(0, arena).with_index(1, bin).read()
+    }
+}
+
+/// An iterator over statistics for each bin size class of an arena.
+///
+/// Returned by [`bins`] and [`Bins::iter`].
+///
+/// [`bins`]: fn.bins.html
+/// [`Bins::iter`]: struct.Bins.html#method.iter
+pub struct BinIter {
+    bins: Bins,
+    arena: c_uint,
+    next: c_uint,
+}
+
+impl Iterator for BinIter {
+    type Item = Result<BinStats>;
+
+    fn next(&mut self) -> Option<Result<BinStats>> {
+        if self.next == self.bins.nbins {
+            return None;
+        }
+        let bin = self.next;
+        self.next += 1;
+        Some(self.bins.get(self.arena, bin))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.bins.nbins - self.next) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for BinIter {}
+
+const NLEXTENTS: &[u8] = b"arenas.nlextents\0";
+const LEXTENT_SIZE: &[u8] = b"arenas.lextent.0.size\0";
+const LEXTENT_NMALLOC: &[u8] = b"stats.arenas.0.lextents.0.nmalloc\0";
+const LEXTENT_NDALLOC: &[u8] = b"stats.arenas.0.lextents.0.ndalloc\0";
+const LEXTENT_NREQUESTS: &[u8] = b"stats.arenas.0.lextents.0.nrequests\0";
+const LEXTENT_CURLEXTENTS: &[u8] = b"stats.arenas.0.lextents.0.curlextents\0";
+
+/// Statistics about the allocations of one large size class made by an arena.
+///
+/// Each large allocation is backed by its own extent.
+#[derive(Debug, Copy, Clone)]
+pub struct LExtentStats {
+    /// The size of the extents in the size class.
+    pub size: usize,
+
+    /// The cumulative number of times an allocation was served by the arena.
+    pub nmalloc: u64,
+
+    /// The cumulative number of times an allocation was returned to the arena.
+    pub ndalloc: u64,
+
+    /// The cumulative number of allocation requests.
+    pub nrequests: u64,
+
+    /// The current number of extents.
+    pub curlextents: usize,
+}
+
+/// Returns an iterator over statistics for each large size class of the arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`epoch`] function for more information.
+///
+/// This combines `arenas.lextent.<j>.size` and `stats.arenas.<i>.lextents.<j>.*` in jemalloc's
+/// API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let _buf = vec![0u8; 1024 * 1024];
+///     jemalloc_ctl::epoch().unwrap();
+///
+///     let lextents = jemalloc_ctl::stats::arenas::lextents(jemalloc_ctl::arenas::ALL).unwrap();
+///     for lextent in lextents {
+///         let lextent = lextent.unwrap();
+///         if lextent.curlextents > 0 {
+///             println!("{} byte extents: {} in use", lextent.size, lextent.curlextents);
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn lextents(arena: c_uint) -> Result<LExtentIter> {
+    LExtents::new().map(|lextents| lextents.iter(arena))
+}
+
+/// A type providing access to statistics for each large size class of an arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`Epoch`] type for more information.
+///
+/// This combines `arenas.lextent.<j>.size` and `stats.arenas.<i>.lextents.<j>.*` in jemalloc's
+/// API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::arenas::LExtents;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let lextents = LExtents::new().unwrap();
+///
+///     epoch.advance().unwrap();
+///     let churn = lextents
+///         .iter(0)
+///         .map(|lextent| lextent.map(|lextent| lextent.nmalloc))
+///         .sum::<Result<u64, _>>()
+///         .unwrap();
+///     println!("{} large allocations", churn);
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct LExtents {
+    nlextents: c_uint,
+    size: Mib<usize, [usize; 4]>,
+    nmalloc: Mib<u64, [usize; 6]>,
+    ndalloc: Mib<u64, [usize; 6]>,
+    nrequests: Mib<u64, [usize; 6]>,
+    curlextents: Mib<usize, [usize; 6]>,
+}
+
+impl LExtents {
+    /// Returns a new `LExtents`.
+    pub fn new() -> Result<LExtents> {
+        unsafe {
+            Ok(LExtents {
+                nlextents: get(NLEXTENTS)?,
+                size: Mib::new(LEXTENT_SIZE)?,
+                nmalloc: Mib::new(LEXTENT_NMALLOC)?,
+                ndalloc: Mib::new(LEXTENT_NDALLOC)?,
+                nrequests: Mib::new(LEXTENT_NREQUESTS)?,
+                curlextents: Mib::new(LEXTENT_CURLEXTENTS)?,
+            })
+        }
+    }
+
+    /// Returns the number of large size classes.
+    pub fn nlextents(&self) -> c_uint {
+        self.nlextents
+    }
+
+    /// Returns statistics for a single large size class of the arena.
+    pub fn get(&self, arena: c_uint, lextent: c_uint) -> Result<LExtentStats> {
+        let arena = arena as usize;
+        let lextent = lextent as usize;
+        Ok(LExtentStats {
+            size: self.size.with_index(0, lextent).read()?,
+            nmalloc: self.stat(self.nmalloc, arena, lextent)?,
+            ndalloc: self.stat(self.ndalloc, arena, lextent)?,
+            nrequests: self.stat(self.nrequests, arena, lextent)?,
+            curlextents: self.stat(self.curlextents, arena, lextent)?,
+        })
+    }
+
+    /// Returns an iterator over statistics for each large size class of the arena.
+    pub fn iter(&self, arena: c_uint) -> LExtentIter {
+        LExtentIter {
+            lextents: *self,
+            arena,
+            next: 0,
+        }
+    }
+
+    fn stat<T>(&self, mib: Mib<T, [usize; 6]>, arena: usize, lextent: usize) -> Result<T>
+    where
+        T: Copy,
+    {
+        mib.with_index(0, arena).with_index(1, lextent).read()
+    }
+}
+
+/// An iterator over statistics for each large size class of an arena.
+///
+/// Returned by [`lextents`] and [`LExtents::iter`].
+///
+/// [`lextents`]: fn.lextents.html
+/// [`LExtents::iter`]: struct.LExtents.html#method.iter
+pub struct LExtentIter {
+    lextents: LExtents,
+    arena: c_uint,
+    next: c_uint,
+}
+
+impl Iterator for LExtentIter {
+    type Item = Result<LExtentStats>;
+
+    fn next(&mut self) -> Option<Result<LExtentStats>> {
+        if self.next == self.lextents.nlextents {
+            return None;
+        }
+        let lextent = self.next;
+        self.next += 1;
+        Some(self.lextents.get(self.arena, lextent))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.lextents.nlextents - self.next) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for LExtentIter {}
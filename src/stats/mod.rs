@@ -5,12 +5,12 @@
 //!
 //! [`Epoch`]: ../struct.Epoch.html
 
-use std::io;
-use std::os::raw::c_char;
+use {get, Mib, Result};
 
-use {get, get_mib, name_to_mib};
+pub mod arenas;
+pub mod mutexes;
 
-const ALLOCATED: *const c_char = b"stats.allocated\0" as *const _ as *const _;
+const ALLOCATED: &[u8] = b"stats.allocated\0";
 
 /// Returns the total number of bytes allocated by the application.
 ///
@@ -38,7 +38,7 @@ const ALLOCATED: *const c_char = b"stats.allocated\0" as *const _ as *const _;
 /// ```
 ///
 /// [`epoch`]: ../fn.epoch().html
-pub fn allocated() -> io::Result<usize> {
+pub fn allocated() -> Result<usize> {
     unsafe { get(ALLOCATED) }
 }
 
@@ -75,25 +75,21 @@ pub fn allocated() -> io::Result<usize> {
 ///
 /// [`Epoch`]: ../struct.Epoch.html
 #[derive(Copy, Clone)]
-pub struct Allocated([usize; 2]);
+pub struct Allocated(Mib<usize, [usize; 2]>);
 
 impl Allocated {
     /// Returns a new `Allocated`.
-    pub fn new() -> io::Result<Allocated> {
-        let mut mib = [0; 2];
-        unsafe {
-            name_to_mib(ALLOCATED, &mut mib)?;
-        }
-        Ok(Allocated(mib))
+    pub fn new() -> Result<Allocated> {
+        unsafe { Mib::new(ALLOCATED).map(Allocated) }
     }
 
     /// Returns the total number of bytes allocated by the application.
-    pub fn get(&self) -> io::Result<usize> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
     }
 }
 
-const ACTIVE: *const c_char = b"stats.active\0" as *const _ as *const _;
+const ACTIVE: &[u8] = b"stats.active\0";
 
 /// Returns the total number of bytes in active pages allocated by the application.
 ///
@@ -125,7 +121,7 @@ const ACTIVE: *const c_char = b"stats.active\0" as *const _ as *const _;
 ///
 /// [`epoch`]: ../fn.epoch().html
 /// [`allocated`]: fn.allocated.hml
-pub fn active() -> io::Result<usize> {
+pub fn active() -> Result<usize> {
     unsafe { get(ACTIVE) }
 }
 
@@ -167,25 +163,21 @@ pub fn active() -> io::Result<usize> {
 /// [`Epoch`]: ../struct.Epoch.html
 /// [`Allocated`]: struct.Allocated.html
 #[derive(Copy, Clone)]
-pub struct Active([usize; 2]);
+pub struct Active(Mib<usize, [usize; 2]>);
 
 impl Active {
     /// Returns a new `Allocated`.
-    pub fn new() -> io::Result<Active> {
-        let mut mib = [0; 2];
-        unsafe {
-            name_to_mib(ACTIVE, &mut mib)?;
-        }
-        Ok(Active(mib))
+    pub fn new() -> Result<Active> {
+        unsafe { Mib::new(ACTIVE).map(Active) }
     }
 
     /// Returns the total number of bytes in active pages allocated by the application.
-    pub fn get(&self) -> io::Result<usize> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
     }
 }
 
-const METADATA: *const c_char = b"stats.metadata\0" as *const _ as *const _;
+const METADATA: &[u8] = b"stats.metadata\0";
 
 /// Returns the total number of bytes dedicated to jemalloc metadata.
 ///
@@ -210,7 +202,7 @@ const METADATA: *const c_char = b"stats.metadata\0" as *const _ as *const _;
 /// ```
 ///
 /// [`epoch`]: ../fn.epoch.html
-pub fn metadata() -> io::Result<usize> {
+pub fn metadata() -> Result<usize> {
     unsafe { get(METADATA) }
 }
 
@@ -245,25 +237,21 @@ pub fn metadata() -> io::Result<usize> {
 ///
 /// [`Epoch`]: ../struct.Epoch.html
 #[derive(Copy, Clone)]
-pub struct Metadata([usize; 2]);
+pub struct Metadata(Mib<usize, [usize; 2]>);
 
 impl Metadata {
     /// Returns a new `Metadata`.
-    pub fn new() -> io::Result<Metadata> {
-        let mut mib = [0; 2];
-        unsafe {
-            name_to_mib(METADATA, &mut mib)?;
-        }
-        Ok(Metadata(mib))
+    pub fn new() -> Result<Metadata> {
+        unsafe { Mib::new(METADATA).map(Metadata) }
     }
 
     /// Returns the total number of bytes dedicated to jemalloc metadata.
-    pub fn get(&self) -> io::Result<usize> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
     }
 }
 
-const RESIDENT: *const c_char = b"stats.resident\0" as *const _ as *const _;
+const RESIDENT: &[u8] = b"stats.resident\0";
 
 /// Returns the total number of bytes in physically resident data pages mapped by the allocator.
 ///
@@ -295,7 +283,7 @@ const RESIDENT: *const c_char = b"stats.resident\0" as *const _ as *const _;
 ///
 /// [`epoch`]: ../fn.epoch.html
 /// [`active`]: fn.active.html
-pub fn resident() -> io::Result<usize> {
+pub fn resident() -> Result<usize> {
     unsafe { get(RESIDENT) }
 }
 
@@ -338,25 +326,21 @@ pub fn resident() -> io::Result<usize> {
 /// [`Epoch`]: ../struct.Epoch.html
 /// [`Active`]: struct.Active.html
 #[derive(Copy, Clone)]
-pub struct Resident([usize; 2]);
+pub struct Resident(Mib<usize, [usize; 2]>);
 
 impl Resident {
     /// Returns a new `Resident`.
-    pub fn new() -> io::Result<Resident> {
-        let mut mib = [0; 2];
-        unsafe {
-            name_to_mib(RESIDENT, &mut mib)?;
-        }
-        Ok(Resident(mib))
+    pub fn new() -> Result<Resident> {
+        unsafe { Mib::new(RESIDENT).map(Resident) }
     }
 
     /// Returns the total number of bytes in physically resident data pages mapped by the allocator.
-    pub fn get(&self) -> io::Result<usize> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
     }
 }
 
-const MAPPED: *const c_char = b"stats.mapped\0" as *const _ as *const _;
+const MAPPED: &[u8] = b"stats.mapped\0";
 
 /// Returns the total number of bytes in active extents mapped by the allocator.
 ///
@@ -387,7 +371,7 @@ const MAPPED: *const c_char = b"stats.mapped\0" as *const _ as *const _;
 /// [`epoch`]: ../fn.epoch.html
 /// [`resident`]: fn.resident.html
 /// [`active`]: fn.active.html
-pub fn mapped() -> io::Result<usize> {
+pub fn mapped() -> Result<usize> {
     unsafe { get(MAPPED) }
 }
 
@@ -428,25 +412,21 @@ pub fn mapped() -> io::Result<usize> {
 /// [`Resident`]: struct.Resident.html
 /// [`Active`]: struct.Active.html
 #[derive(Copy, Clone)]
-pub struct Mapped([usize; 2]);
+pub struct Mapped(Mib<usize, [usize; 2]>);
 
 impl Mapped {
     /// Returns a new `Mapped`.
-    pub fn new() -> io::Result<Mapped> {
-        let mut mib = [0; 2];
-        unsafe {
-            name_to_mib(MAPPED, &mut mib)?;
-        }
-        Ok(Mapped(mib))
+    pub fn new() -> Result<Mapped> {
+        unsafe { Mib::new(MAPPED).map(Mapped) }
     }
 
     /// Returns the total number of bytes in active extents mapped by the allocator.
-    pub fn get(&self) -> io::Result<usize> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
     }
 }
 
-const RETAINED: *const c_char = b"stats.retained\0" as *const _ as *const _;
+const RETAINED: &[u8] = b"stats.retained\0";
 
 /// Returns the total number of bytes in virtual memory mappings that were retained rather than being returned to the
 /// operating system via e.g. `munmap(2)`.
@@ -476,7 +456,7 @@ const RETAINED: *const c_char = b"stats.retained\0" as *const _ as *const _;
 ///
 /// [`epoch`]: ../fn.epoch.html
 /// [`mapped`]: fn.mapped.html
-pub fn retained() -> io::Result<usize> {
+pub fn retained() -> Result<usize> {
     unsafe { get(RETAINED) }
 }
 
@@ -516,20 +496,16 @@ pub fn retained() -> io::Result<usize> {
 /// [`Epoch`]: ../struct.Epoch.html
 /// [`Mapped`]: struct.Mapped.html
 #[derive(Copy, Clone)]
-pub struct Retained([usize; 2]);
+pub struct Retained(Mib<usize, [usize; 2]>);
 
 impl Retained {
     /// Returns a new `Retained`.
-    pub fn new() -> io::Result<Retained> {
-        let mut mib = [0; 2];
-        unsafe {
-            name_to_mib(RETAINED, &mut mib)?;
-        }
-        Ok(Retained(mib))
+    pub fn new() -> Result<Retained> {
+        unsafe { Mib::new(RETAINED).map(Retained) }
     }
 
     /// Returns the total number of bytes in virtual memory mappings that were retained.
-    pub fn get(&self) -> io::Result<usize> {
-        unsafe { get_mib(&self.0) }
+    pub fn get(&self) -> Result<usize> {
+        self.0.read()
     }
 }
@@ -0,0 +1,431 @@
+//! Mutex contention statistics.
+//!
+//! jemalloc profiles the mutexes which protect its global and per-arena state. The global mutexes
+//! are identified by [`GlobalMutex`], and the mutexes each arena owns by [`ArenaMutex`].
+//!
+//! Like the other statistics, these are cached and only refreshed when the epoch is advanced. See
+//! the [`Epoch`] type for more information. The counters accumulate from the start of the program
+//! until they are cleared with [`reset`].
+//!
+//! # Examples
+//!
+//! ```
+//! extern crate jemallocator;
+//! extern crate jemalloc_ctl;
+//!
+//! use jemalloc_ctl::arenas;
+//! use jemalloc_ctl::stats::mutexes::{self, ArenaMutex, GlobalMutex};
+//!
+//! #[global_allocator]
+//! static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+//!
+//! fn main() {
+//!     jemalloc_ctl::epoch().unwrap();
+//!
+//!     for &mutex in GlobalMutex::all() {
+//!         let stats = mutexes::global(mutex).unwrap();
+//!         println!("{}: {} waits", mutex.name(), stats.num_wait);
+//!     }
+//!     for &mutex in ArenaMutex::all() {
+//!         let stats = mutexes::arena(arenas::ALL, mutex).unwrap();
+//!         println!("{}: {} waits", mutex.name(), stats.num_wait);
+//!     }
+//! }
+//! ```
+//!
+//! [`GlobalMutex`]: enum.GlobalMutex.html
+//! [`ArenaMutex`]: enum.ArenaMutex.html
+//! [`Epoch`]: ../../struct.Epoch.html
+//! [`reset`]: fn.reset.html
+use std::os::raw::c_uint;
+
+use {run, Mib, MibLen, Result};
+
+// Expands to the names of the statistics for a mutex, in the order `MutexMibs::new` expects.
+macro_rules! mutex_names {
+    ($prefix:expr, $name:expr) => {
+        [
+            concat!($prefix, $name, ".num_ops\0"),
+            concat!($prefix, $name, ".num_wait\0"),
+            concat!($prefix, $name, ".num_spin_acq\0"),
+            concat!($prefix, $name, ".num_owner_switch\0"),
+            concat!($prefix, $name, ".total_wait_time\0"),
+            concat!($prefix, $name, ".max_wait_time\0"),
+            concat!($prefix, $name, ".max_num_thds\0"),
+        ]
+    };
+}
+
+macro_rules! mutexes {
+    (
+        $(#[$attr:meta])*
+        pub enum $ty:ident: $prefix:tt {
+            $($(#[$vattr:meta])* $variant:ident => $name:expr,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum $ty {
+            $($(#[$vattr])* $variant,)*
+        }
+
+        impl $ty {
+            /// Returns all of the mutexes.
+            pub fn all() -> &'static [$ty] {
+                &[$($ty::$variant,)*]
+            }
+
+            /// Returns jemalloc's name for the mutex.
+            pub fn name(&self) -> &'static str {
+                match *self {
+                    $($ty::$variant => $name,)*
+                }
+            }
+
+            fn stat_names(&self) -> &'static [&'static str; 7] {
+                match *self {
+                    $($ty::$variant => &mutex_names!($prefix, $name),)*
+                }
+            }
+        }
+    };
+}
+
+mutexes! {
+    /// A mutex protecting global allocator state.
+    pub enum GlobalMutex: "stats.mutexes." {
+        /// The mutex protecting the background threads.
+        BackgroundThread => "background_thread",
+        /// The mutex serializing `mallctl` operations.
+        Ctl => "ctl",
+        /// The mutex protecting the heap profiler.
+        Prof => "prof",
+    }
+}
+
+mutexes! {
+    /// A mutex owned by each arena.
+    pub enum ArenaMutex: "stats.arenas.0.mutexes." {
+        /// The mutex protecting the arena's list of large allocations.
+        Large => "large",
+        /// The mutex protecting the arena's cache of extent structures.
+        ExtentAvail => "extent_avail",
+        /// The mutex protecting the arena's dirty extents.
+        ExtentsDirty => "extents_dirty",
+        /// The mutex protecting the arena's muzzy extents.
+        ExtentsMuzzy => "extents_muzzy",
+        /// The mutex protecting the arena's retained extents.
+        ExtentsRetained => "extents_retained",
+        /// The mutex protecting the arena's dirty page decay state.
+        DecayDirty => "decay_dirty",
+        /// The mutex protecting the arena's muzzy page decay state.
+        DecayMuzzy => "decay_muzzy",
+        /// The mutex protecting the arena's metadata allocator.
+        Base => "base",
+        /// The mutex protecting the list of thread caches associated with the arena.
+        TcacheList => "tcache_list",
+    }
+}
+
+/// Contention statistics for a mutex.
+#[derive(Debug, Copy, Clone)]
+pub struct MutexStats {
+    /// The number of times the mutex was acquired.
+    pub num_ops: u64,
+
+    /// The number of times a thread had to block while acquiring the mutex.
+    pub num_wait: u64,
+
+    /// The number of times the mutex was acquired after spinning.
+    pub num_spin_acq: u64,
+
+    /// The number of times the thread owning the mutex changed.
+    pub num_owner_switch: u64,
+
+    /// The total time spent waiting to acquire the mutex, in nanoseconds.
+    pub total_wait_time: u64,
+
+    /// The longest time spent waiting to acquire the mutex, in nanoseconds.
+    pub max_wait_time: u64,
+
+    /// The largest number of threads which were waiting on the mutex at once.
+    pub max_num_thds: u32,
+}
+
+struct MutexMibs<L> {
+    num_ops: Mib<u64, L>,
+    num_wait: Mib<u64, L>,
+    num_spin_acq: Mib<u64, L>,
+    num_owner_switch: Mib<u64, L>,
+    total_wait_time: Mib<u64, L>,
+    max_wait_time: Mib<u64, L>,
+    max_num_thds: Mib<u32, L>,
+}
+
+impl<L> Copy for MutexMibs<L> where L: MibLen {}
+
+impl<L> Clone for MutexMibs<L>
+where
+    L: MibLen,
+{
+    fn clone(&self) -> MutexMibs<L> {
+        *self
+    }
+}
+
+impl<L> MutexMibs<L>
+where
+    L: MibLen,
+{
+    unsafe fn new(names: &[&'static str; 7]) -> Result<MutexMibs<L>> {
+        Ok(MutexMibs {
+            num_ops: Mib::new(names[0].as_bytes())?,
+            num_wait: Mib::new(names[1].as_bytes())?,
+            num_spin_acq: Mib::new(names[2].as_bytes())?,
+            num_owner_switch: Mib::new(names[3].as_bytes())?,
+            total_wait_time: Mib::new(names[4].as_bytes())?,
+            max_wait_time: Mib::new(names[5].as_bytes())?,
+            max_num_thds: Mib::new(names[6].as_bytes())?,
+        })
+    }
+
+    fn get(&self, arena: Option<usize>) -> Result<MutexStats> {
+        fn read<T, L>(mib: Mib<T, L>, arena: Option<usize>) -> Result<T>
+        where
+            T: Copy,
+            L: MibLen,
+        {
+            match arena {
+                Some(arena) => mib.with_index(0, arena).read(),
+                None => mib.read(),
+            }
+        }
+
+        Ok(MutexStats {
+            num_ops: read(self.num_ops, arena)?,
+            num_wait: read(self.num_wait, arena)?,
+            num_spin_acq: read(self.num_spin_acq, arena)?,
+            num_owner_switch: read(self.num_owner_switch, arena)?,
+            total_wait_time: read(self.total_wait_time, arena)?,
+            max_wait_time: read(self.max_wait_time, arena)?,
+            max_num_thds: read(self.max_num_thds, arena)?,
+        })
+    }
+}
+
+/// Returns contention statistics for a global mutex.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`epoch`] function for more information.
+///
+/// This corresponds to `stats.mutexes.<name>.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::stats::mutexes::{self, GlobalMutex};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let stats = mutexes::global(GlobalMutex::Ctl).unwrap();
+///     // advancing the epoch acquired the ctl mutex
+///     assert!(stats.num_ops > 0);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn global(mutex: GlobalMutex) -> Result<MutexStats> {
+    Global::new(mutex)?.get()
+}
+
+/// A type providing access to contention statistics for a global mutex.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`Epoch`] type for more information.
+///
+/// This corresponds to `stats.mutexes.<name>.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::Epoch;
+/// use jemalloc_ctl::stats::mutexes::{Global, GlobalMutex};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let epoch = Epoch::new().unwrap();
+///     let ctl = Global::new(GlobalMutex::Ctl).unwrap();
+///
+///     epoch.advance().unwrap();
+///     let a = ctl.get().unwrap();
+///     epoch.advance().unwrap();
+///     let b = ctl.get().unwrap();
+///     assert!(a.num_ops < b.num_ops);
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct Global(MutexMibs<[usize; 4]>);
+
+impl Global {
+    /// Returns a new `Global` for the specified mutex.
+    pub fn new(mutex: GlobalMutex) -> Result<Global> {
+        unsafe { MutexMibs::new(mutex.stat_names()).map(Global) }
+    }
+
+    /// Returns contention statistics for the mutex.
+    pub fn get(&self) -> Result<MutexStats> {
+        self.0.get(None)
+    }
+}
+
+/// Returns contention statistics for a mutex owned by the arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`epoch`] function for more information.
+///
+/// This corresponds to `stats.arenas.<i>.mutexes.<name>.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::stats::mutexes::{self, ArenaMutex};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///     let stats = mutexes::arena(0, ArenaMutex::Large).unwrap();
+///     println!("{} ns spent waiting", stats.total_wait_time);
+/// }
+/// ```
+///
+/// [`epoch`]: ../../fn.epoch.html
+pub fn arena(arena: c_uint, mutex: ArenaMutex) -> Result<MutexStats> {
+    Arena::new(mutex)?.get(arena)
+}
+
+/// A type providing access to contention statistics for a mutex owned by each arena.
+///
+/// These statistics are cached, and are only refreshed when the epoch is advanced. See the
+/// [`Epoch`] type for more information.
+///
+/// This corresponds to `stats.arenas.<i>.mutexes.<name>.*` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas;
+/// use jemalloc_ctl::stats::mutexes::{Arena, ArenaMutex};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::epoch().unwrap();
+///
+///     let large = Arena::new(ArenaMutex::Large).unwrap();
+///     for arena in 0..arenas::narenas().unwrap() {
+///         if let Ok(stats) = large.get(arena) {
+///             println!("arena {}: {} waits", arena, stats.num_wait);
+///         }
+///     }
+/// }
+/// ```
+///
+/// [`Epoch`]: ../../struct.Epoch.html
+#[derive(Copy, Clone)]
+pub struct Arena(MutexMibs<[usize; 6]>);
+
+impl Arena {
+    /// Returns a new `Arena` for the specified mutex.
+    pub fn new(mutex: ArenaMutex) -> Result<Arena> {
+        unsafe { MutexMibs::new(mutex.stat_names()).map(Arena) }
+    }
+
+    /// Returns contention statistics for the mutex owned by the arena.
+    pub fn get(&self, arena: c_uint) -> Result<MutexStats> {
+        self.0.get(Some(arena as usize))
+    }
+}
+
+const RESET: &[u8] = b"stats.mutexes.reset\0";
+
+/// Resets the contention statistics of all global and arena mutexes.
+///
+/// This corresponds to `stats.mutexes.reset` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::stats::mutexes::{self, GlobalMutex};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     mutexes::reset().unwrap();
+///     jemalloc_ctl::epoch().unwrap();
+///     println!("{:?}", mutexes::global(GlobalMutex::Ctl).unwrap());
+/// }
+/// ```
+pub fn reset() -> Result<()> {
+    unsafe { run(RESET) }
+}
+
+/// A type providing access to the reset of mutex contention statistics.
+///
+/// This corresponds to `stats.mutexes.reset` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::stats::mutexes::Reset;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let reset = Reset::new().unwrap();
+///     reset.reset().unwrap();
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Reset(Mib<(), [usize; 3]>);
+
+impl Reset {
+    /// Returns a new `Reset`.
+    pub fn new() -> Result<Reset> {
+        unsafe { Mib::new(RESET).map(Reset) }
+    }
+
+    /// Resets the contention statistics of all global and arena mutexes.
+    pub fn reset(&self) -> Result<()> {
+        self.0.run()
+    }
+}
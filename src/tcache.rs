@@ -0,0 +1,260 @@
+//! Explicit thread cache operations.
+//!
+//! By default, each thread uses an automatically managed thread cache. Explicit thread caches can
+//! be created in addition, and selected with the `MALLOCX_TCACHE` flag (see [`Tcache::flags`]).
+//! The automatically managed cache of the current thread is controlled through the [`thread`]
+//! module.
+//!
+//! [`Tcache::flags`]: struct.Tcache.html#method.flags
+//! [`thread`]: ../thread/index.html
+use jemalloc_sys;
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::os::raw::{c_int, c_uint};
+
+use {Mib, Result};
+
+const CREATE: &[u8] = b"tcache.create\0";
+const FLUSH: &[u8] = b"tcache.flush\0";
+const DESTROY: &[u8] = b"tcache.destroy\0";
+
+/// An explicit thread cache.
+///
+/// The cache is destroyed when the `Tcache` is dropped, returning the regions it holds to their
+/// arenas.
+///
+/// A cache can be used from any thread, but only by one thread at a time, so `Tcache` is `Send`
+/// but not `Sync`.
+pub struct Tcache {
+    id: c_uint,
+    flush: Flush,
+    destroy: Destroy,
+    _p: PhantomData<Cell<()>>,
+}
+
+impl Tcache {
+    /// Returns the cache's identifier.
+    pub fn id(&self) -> c_uint {
+        self.id
+    }
+
+    /// Returns the `MALLOCX_TCACHE` flag selecting this cache, for use with `mallocx` and related
+    /// functions.
+    pub fn flags(&self) -> c_int {
+        jemalloc_sys::MALLOCX_TCACHE(self.id as usize)
+    }
+
+    /// Flushes the cache, returning the regions it holds to their arenas.
+    ///
+    /// This corresponds to `tcache.flush` in jemalloc's API.
+    pub fn flush(&self) -> Result<()> {
+        self.flush.flush(self)
+    }
+
+    /// Destroys the cache.
+    ///
+    /// This is equivalent to dropping the `Tcache`, but reports errors.
+    ///
+    /// This corresponds to `tcache.destroy` in jemalloc's API.
+    pub fn destroy(self) -> Result<()> {
+        let destroy = self.destroy;
+        destroy.destroy(self)
+    }
+}
+
+impl fmt::Debug for Tcache {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("Tcache").field("id", &self.id).finish()
+    }
+}
+
+impl Drop for Tcache {
+    fn drop(&mut self) {
+        let _ = self.destroy.0.write(self.id);
+    }
+}
+
+/// Creates an explicit thread cache.
+///
+/// This corresponds to `tcache.create` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+/// extern crate jemalloc_sys;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let tcache = jemalloc_ctl::tcache::create().unwrap();
+///
+///     unsafe {
+///         let ptr = jemalloc_sys::mallocx(64, tcache.flags());
+///         assert!(!ptr.is_null());
+///         jemalloc_sys::dallocx(ptr, tcache.flags());
+///     }
+///
+///     tcache.flush().unwrap();
+/// }
+/// ```
+pub fn create() -> Result<Tcache> {
+    Create::new()?.create()
+}
+
+/// A type providing access to the creation of explicit thread caches.
+///
+/// This corresponds to `tcache.create` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::tcache::Create;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let create = Create::new().unwrap();
+///
+///     let a = create.create().unwrap();
+///     let b = create.create().unwrap();
+///     assert!(a.id() != b.id());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Create {
+    mib: Mib<c_uint, [usize; 2]>,
+    flush: Flush,
+    destroy: Destroy,
+}
+
+impl Create {
+    /// Returns a new `Create`.
+    pub fn new() -> Result<Create> {
+        unsafe {
+            Ok(Create {
+                mib: Mib::new(CREATE)?,
+                flush: Flush::new()?,
+                destroy: Destroy::new()?,
+            })
+        }
+    }
+
+    /// Creates an explicit thread cache.
+    pub fn create(&self) -> Result<Tcache> {
+        self.mib.read().map(|id| Tcache {
+            id,
+            flush: self.flush,
+            destroy: self.destroy,
+            _p: PhantomData,
+        })
+    }
+}
+
+/// A type providing access to the flushing of explicit thread caches.
+///
+/// This corresponds to `tcache.flush` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::tcache::{self, Flush};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let flush = Flush::new().unwrap();
+///     let tcache = tcache::create().unwrap();
+///
+///     flush.flush(&tcache).unwrap();
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Flush(Mib<c_uint, [usize; 2]>);
+
+impl Flush {
+    /// Returns a new `Flush`.
+    pub fn new() -> Result<Flush> {
+        unsafe { Mib::new(FLUSH).map(Flush) }
+    }
+
+    /// Flushes a cache, returning the regions it holds to their arenas.
+    pub fn flush(&self, tcache: &Tcache) -> Result<()> {
+        self.0.write(tcache.id)
+    }
+}
+
+/// A type providing access to the destruction of explicit thread caches.
+///
+/// This corresponds to `tcache.destroy` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::tcache::{self, Destroy};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let destroy = Destroy::new().unwrap();
+///     let tcache = tcache::create().unwrap();
+///
+///     destroy.destroy(tcache).unwrap();
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Destroy(Mib<c_uint, [usize; 2]>);
+
+impl Destroy {
+    /// Returns a new `Destroy`.
+    pub fn new() -> Result<Destroy> {
+        unsafe { Mib::new(DESTROY).map(Destroy) }
+    }
+
+    /// Destroys a cache.
+    ///
+    /// This is equivalent to dropping the `Tcache`, but reports errors.
+    pub fn destroy(&self, tcache: Tcache) -> Result<()> {
+        let id = tcache.id;
+        mem::forget(tcache);
+        self.0.write(id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn create_use_destroy() {
+        let a = create().unwrap();
+        let b = create().unwrap();
+        assert!(a.id() != b.id());
+
+        unsafe {
+            let ptr = jemalloc_sys::mallocx(64, a.flags());
+            assert!(!ptr.is_null());
+            jemalloc_sys::dallocx(ptr, a.flags());
+        }
+
+        a.flush().unwrap();
+        a.destroy().unwrap();
+        drop(b);
+    }
+}
@@ -1,10 +1,88 @@
 //! Thread specific operations.
-use std::io;
-use std::os::raw::c_char;
+use libc::c_char;
+use std::ffi::CStr;
+use std::marker::PhantomData;
+use std::mem;
+use std::os::raw::c_uint;
 
-use {get, get_mib, name_to_mib};
+use arenas::ArenaId;
+use {get, name_string, run, set, set_str, set_str_mib, Error, Mib, Result, ToCStr};
 
-const ALLOCATEDP: *const c_char = b"thread.allocatedp\0" as *const _ as *const _;
+const ALLOCATED: &[u8] = b"thread.allocated\0";
+
+/// Returns the total number of bytes allocated by the current thread.
+///
+/// Unlike [`allocatedp`], this performs a `mallctl` call every time. It is more convenient for
+/// infrequent reads.
+///
+/// This corresponds to `thread.allocated` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let a = jemalloc_ctl::thread::allocated().unwrap();
+///     let buf = vec![0; 1024 * 1024];
+///     drop(buf);
+///     let b = jemalloc_ctl::thread::allocated().unwrap();
+///
+///     assert!(a < b);
+/// }
+/// ```
+///
+/// [`allocatedp`]: fn.allocatedp.html
+pub fn allocated() -> Result<u64> {
+    unsafe { get(ALLOCATED) }
+}
+
+/// A type providing access to the total number of bytes allocated by the current thread.
+///
+/// This corresponds to `thread.allocated` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::thread::Allocated;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let allocated = Allocated::new().unwrap();
+///
+///     let a = allocated.get().unwrap();
+///     let buf = vec![0; 1024 * 1024];
+///     drop(buf);
+///     let b = allocated.get().unwrap();
+///
+///     assert!(a < b);
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Allocated(Mib<u64, [usize; 2]>);
+
+impl Allocated {
+    /// Returns a new `Allocated`.
+    pub fn new() -> Result<Allocated> {
+        unsafe { Mib::new(ALLOCATED).map(Allocated) }
+    }
+
+    /// Returns the total number of bytes allocated by the current thread.
+    pub fn get(&self) -> Result<u64> {
+        self.0.read()
+    }
+}
+
+const ALLOCATEDP: &[u8] = b"thread.allocatedp\0";
 
 /// Returns a thread-local pointer to the total number of bytes allocated by the current thread.
 ///
@@ -41,7 +119,7 @@ const ALLOCATEDP: *const c_char = b"thread.allocatedp\0" as *const _ as *const _
 ///     assert_eq!(b, c);
 /// }
 /// ```
-pub fn allocatedp() -> io::Result<ThreadLocal<u64>> {
+pub fn allocatedp() -> Result<ThreadLocal<u64>> {
     unsafe { get(ALLOCATEDP).map(ThreadLocal) }
 }
 
@@ -84,25 +162,95 @@ pub fn allocatedp() -> io::Result<ThreadLocal<u64>> {
 ///
 /// [`stats::Allocated`]: ../stats/struct.Allocated.html
 #[derive(Copy, Clone)]
-pub struct AllocatedP([usize; 2]);
+pub struct AllocatedP(Mib<*const u64, [usize; 2]>);
 
 impl AllocatedP {
     /// Returns a new `Allocated`.
-    pub fn new() -> io::Result<AllocatedP> {
-        let mut mib = [0; 2];
-        unsafe {
-            name_to_mib(ALLOCATEDP, &mut mib)?;
-        }
-        Ok(AllocatedP(mib))
+    pub fn new() -> Result<AllocatedP> {
+        unsafe { Mib::new(ALLOCATEDP).map(AllocatedP) }
     }
 
     /// Returns a thread-local pointer to the total number of bytes allocated by this thread.
-    pub fn get(&self) -> io::Result<ThreadLocal<u64>> {
-        unsafe { get_mib(&self.0).map(ThreadLocal) }
+    pub fn get(&self) -> Result<ThreadLocal<u64>> {
+        self.0.read().map(ThreadLocal)
+    }
+}
+
+const DEALLOCATED: &[u8] = b"thread.deallocated\0";
+
+/// Returns the total number of bytes deallocated by the current thread.
+///
+/// Unlike [`deallocatedp`], this performs a `mallctl` call every time. It is more convenient for
+/// infrequent reads.
+///
+/// This corresponds to `thread.deallocated` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let a = jemalloc_ctl::thread::deallocated().unwrap();
+///     let buf = vec![0; 1024 * 1024];
+///     drop(buf);
+///     let b = jemalloc_ctl::thread::deallocated().unwrap();
+///
+///     assert!(a < b);
+/// }
+/// ```
+///
+/// [`deallocatedp`]: fn.deallocatedp.html
+pub fn deallocated() -> Result<u64> {
+    unsafe { get(DEALLOCATED) }
+}
+
+/// A type providing access to the total number of bytes deallocated by the current thread.
+///
+/// This corresponds to `thread.deallocated` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::thread::Deallocated;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let deallocated = Deallocated::new().unwrap();
+///
+///     let a = deallocated.get().unwrap();
+///     let buf = vec![0; 1024 * 1024];
+///     drop(buf);
+///     let b = deallocated.get().unwrap();
+///
+///     assert!(a < b);
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Deallocated(Mib<u64, [usize; 2]>);
+
+impl Deallocated {
+    /// Returns a new `Deallocated`.
+    pub fn new() -> Result<Deallocated> {
+        unsafe { Mib::new(DEALLOCATED).map(Deallocated) }
+    }
+
+    /// Returns the total number of bytes deallocated by the current thread.
+    pub fn get(&self) -> Result<u64> {
+        self.0.read()
     }
 }
 
-const DEALLOCATEDP: *const c_char = b"thread.deallocatedp\0" as *const _ as *const _;
+const DEALLOCATEDP: &[u8] = b"thread.deallocatedp\0";
 
 /// Returns a pointer to the total number of bytes deallocated by the current thread.
 ///
@@ -135,7 +283,7 @@ const DEALLOCATEDP: *const c_char = b"thread.deallocatedp\0" as *const _ as *con
 ///     assert!(b < c);
 /// }
 /// ```
-pub fn deallocatedp() -> io::Result<ThreadLocal<u64>> {
+pub fn deallocatedp() -> Result<ThreadLocal<u64>> {
     unsafe { get(DEALLOCATEDP).map(ThreadLocal) }
 }
 
@@ -172,24 +320,637 @@ pub fn deallocatedp() -> io::Result<ThreadLocal<u64>> {
 /// }
 /// ```
 #[derive(Copy, Clone)]
-pub struct DeallocatedP([usize; 2]);
+pub struct DeallocatedP(Mib<*const u64, [usize; 2]>);
 
 impl DeallocatedP {
     /// Returns a new `Deallocated`.
-    pub fn new() -> io::Result<DeallocatedP> {
-        let mut mib = [0; 2];
-        unsafe {
-            name_to_mib(DEALLOCATEDP, &mut mib)?;
-        }
-        Ok(DeallocatedP(mib))
+    pub fn new() -> Result<DeallocatedP> {
+        unsafe { Mib::new(DEALLOCATEDP).map(DeallocatedP) }
     }
 
     /// Returns a thread-local pointer to the total number of bytes deallocated by this thread.
-    pub fn get(&self) -> io::Result<ThreadLocal<u64>> {
-        unsafe {
-            let ptr = get_mib::<*mut u64>(&self.0)?;
-            Ok(ThreadLocal(ptr))
-        }
+    pub fn get(&self) -> Result<ThreadLocal<u64>> {
+        self.0.read().map(ThreadLocal)
+    }
+}
+
+const ARENA: &[u8] = b"thread.arena\0";
+
+/// Returns the arena the current thread is bound to.
+///
+/// This corresponds to `thread.arena` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let arena = jemalloc_ctl::thread::arena().unwrap();
+///     println!("bound to arena {}", arena.index());
+/// }
+/// ```
+pub fn arena() -> Result<ArenaId> {
+    unsafe { get::<c_uint>(ARENA).map(ArenaId::from) }
+}
+
+/// Binds the current thread to an arena.
+///
+/// Allocations made by the thread which do not explicitly select an arena are served from the
+/// arena it is bound to. See [`bind_arena`] for a version which restores the previous binding.
+///
+/// This corresponds to `thread.arena` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::{arenas, thread};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let arena = arenas::create().unwrap();
+///     thread::set_arena(arena).unwrap();
+///     assert_eq!(thread::arena().unwrap(), arena);
+/// }
+/// ```
+///
+/// [`bind_arena`]: fn.bind_arena.html
+pub fn set_arena(arena: ArenaId) -> Result<()> {
+    unsafe { set(ARENA, arena.index()) }
+}
+
+/// Binds the current thread to an arena until the returned guard is dropped.
+///
+/// When the guard is dropped, the thread is bound to the arena it was bound to before this call.
+/// If that arena is destroyed while the guard is alive, for example by another thread, the binding
+/// cannot be restored and the thread remains bound to `arena`. Use [`ArenaGuard::restore`] to
+/// detect this.
+///
+/// This corresponds to `thread.arena` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::{arenas, thread};
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let original = thread::arena().unwrap();
+///     let arena = arenas::create().unwrap();
+///
+///     {
+///         let _guard = thread::bind_arena(arena).unwrap();
+///         assert_eq!(thread::arena().unwrap(), arena);
+///         let _buf = vec![0u8; 1024];
+///     }
+///
+///     assert_eq!(thread::arena().unwrap(), original);
+/// }
+/// ```
+///
+/// [`ArenaGuard::restore`]: struct.ArenaGuard.html#method.restore
+pub fn bind_arena(arena: ArenaId) -> Result<ArenaGuard> {
+    Arena::new()?.bind(arena)
+}
+
+/// A type providing access to the arena the current thread is bound to.
+///
+/// This corresponds to `thread.arena` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::arenas;
+/// use jemalloc_ctl::thread::Arena;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let thread_arena = Arena::new().unwrap();
+///     let arena = arenas::create().unwrap();
+///
+///     thread_arena.set(arena).unwrap();
+///     assert_eq!(thread_arena.get().unwrap(), arena);
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct Arena(Mib<c_uint, [usize; 2]>);
+
+impl Arena {
+    /// Returns a new `Arena`.
+    pub fn new() -> Result<Arena> {
+        unsafe { Mib::new(ARENA).map(Arena) }
+    }
+
+    /// Returns the arena the current thread is bound to.
+    pub fn get(&self) -> Result<ArenaId> {
+        self.0.read().map(ArenaId::from)
+    }
+
+    /// Binds the current thread to an arena.
+    pub fn set(&self, arena: ArenaId) -> Result<()> {
+        self.0.write(arena.index())
+    }
+
+    /// Binds the current thread to an arena until the returned guard is dropped.
+    pub fn bind(&self, arena: ArenaId) -> Result<ArenaGuard> {
+        let previous = self.0.update(arena.index())?;
+        Ok(ArenaGuard {
+            mib: *self,
+            previous: ArenaId::from(previous),
+            _p: PhantomData,
+        })
+    }
+}
+
+/// A guard which restores the current thread's previous arena binding when dropped.
+///
+/// Returned by [`bind_arena`] and [`Arena::bind`]. It is neither `Sync` nor `Send`, since the
+/// binding belongs to the thread which created it.
+///
+/// [`bind_arena`]: fn.bind_arena.html
+/// [`Arena::bind`]: struct.Arena.html#method.bind
+pub struct ArenaGuard {
+    mib: Arena,
+    previous: ArenaId,
+    _p: PhantomData<*const ()>,
+}
+
+impl ArenaGuard {
+    /// Returns the arena the thread was bound to before the guard was created.
+    pub fn previous(&self) -> ArenaId {
+        self.previous
+    }
+
+    /// Restores the thread's previous arena binding.
+    ///
+    /// This is equivalent to dropping the guard, but reports errors. In particular, an error is
+    /// returned if the previous arena has been destroyed since the guard was created, in which
+    /// case the thread remains bound to the guard's arena.
+    pub fn restore(self) -> Result<()> {
+        let mib = self.mib;
+        let previous = self.previous;
+        mem::forget(self);
+        mib.set(previous)
+    }
+}
+
+impl Drop for ArenaGuard {
+    fn drop(&mut self) {
+        // this fails if the previous arena was destroyed while the guard was alive, which leaves
+        // the thread bound to the guard's arena; `restore` reports the error instead
+        let _ = self.mib.set(self.previous);
+    }
+}
+
+const TCACHE_ENABLED: &[u8] = b"thread.tcache.enabled\0";
+
+/// Determines if the current thread's automatically managed thread cache is enabled.
+///
+/// This corresponds to `thread.tcache.enabled` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     println!("tcache enabled: {}", jemalloc_ctl::thread::tcache_enabled().unwrap());
+/// }
+/// ```
+pub fn tcache_enabled() -> Result<bool> {
+    unsafe { get(TCACHE_ENABLED) }
+}
+
+/// Enables or disables the current thread's automatically managed thread cache.
+///
+/// Disabling the cache flushes it.
+///
+/// This corresponds to `thread.tcache.enabled` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     jemalloc_ctl::thread::set_tcache_enabled(false).unwrap();
+///     assert!(!jemalloc_ctl::thread::tcache_enabled().unwrap());
+/// }
+/// ```
+pub fn set_tcache_enabled(tcache_enabled: bool) -> Result<()> {
+    unsafe { set(TCACHE_ENABLED, tcache_enabled) }
+}
+
+/// A type providing access to the state of the current thread's automatically managed thread
+/// cache.
+///
+/// This corresponds to `thread.tcache.enabled` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::thread::TcacheEnabled;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let tcache_enabled = TcacheEnabled::new().unwrap();
+///
+///     tcache_enabled.set(false).unwrap();
+///     assert!(!tcache_enabled.get().unwrap());
+///     tcache_enabled.set(true).unwrap();
+///     assert!(tcache_enabled.get().unwrap());
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct TcacheEnabled(Mib<bool, [usize; 3]>);
+
+impl TcacheEnabled {
+    /// Returns a new `TcacheEnabled`.
+    pub fn new() -> Result<TcacheEnabled> {
+        unsafe { Mib::new(TCACHE_ENABLED).map(TcacheEnabled) }
+    }
+
+    /// Determines if the current thread's automatically managed thread cache is enabled.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+
+    /// Enables or disables the current thread's automatically managed thread cache.
+    pub fn set(&self, tcache_enabled: bool) -> Result<()> {
+        self.0.write(tcache_enabled)
+    }
+}
+
+const TCACHE_FLUSH: &[u8] = b"thread.tcache.flush\0";
+
+/// Flushes the current thread's automatically managed thread cache.
+///
+/// This returns the cached regions to their arenas, which is useful before a thread becomes idle
+/// for a long time.
+///
+/// This corresponds to `thread.tcache.flush` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     drop(vec![0u8; 64]);
+///     jemalloc_ctl::thread::tcache_flush().unwrap();
+/// }
+/// ```
+pub fn tcache_flush() -> Result<()> {
+    unsafe { run(TCACHE_FLUSH) }
+}
+
+/// A type providing access to flushing the current thread's automatically managed thread cache.
+///
+/// This corresponds to `thread.tcache.flush` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::thread::TcacheFlush;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let tcache_flush = TcacheFlush::new().unwrap();
+///
+///     tcache_flush.flush().unwrap();
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct TcacheFlush(Mib<(), [usize; 3]>);
+
+impl TcacheFlush {
+    /// Returns a new `TcacheFlush`.
+    pub fn new() -> Result<TcacheFlush> {
+        unsafe { Mib::new(TCACHE_FLUSH).map(TcacheFlush) }
+    }
+
+    /// Flushes the current thread's automatically managed thread cache.
+    pub fn flush(&self) -> Result<()> {
+        self.0.run()
+    }
+}
+
+const PROF_NAME: &[u8] = b"thread.prof.name\0";
+
+/// Returns the name associated with the current thread in memory profile dumps.
+///
+/// This corresponds to `thread.prof.name` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(name) = jemalloc_ctl::thread::prof_name() {
+///         println!("profile name: {}", name);
+///     }
+/// }
+/// ```
+pub fn prof_name() -> Result<String> {
+    ProfName::new()?.get()
+}
+
+/// Sets the name associated with the current thread in memory profile dumps.
+///
+/// The name may be any [`ToCStr`] value, and may only contain printable characters and spaces.
+///
+/// This corresponds to `thread.prof.name` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if jemalloc_ctl::thread::set_prof_name("worker 1").is_ok() {
+///         assert_eq!(jemalloc_ctl::thread::prof_name().unwrap(), "worker 1");
+///     }
+/// }
+/// ```
+///
+/// [`ToCStr`]: ../trait.ToCStr.html
+pub fn set_prof_name<S>(name: &S) -> Result<()>
+where
+    S: ToCStr + ?Sized,
+{
+    unsafe { set_str(PROF_NAME, name) }
+}
+
+/// A type providing access to the name associated with the current thread in memory profile
+/// dumps.
+///
+/// This corresponds to `thread.prof.name` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::thread::ProfName;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_name = ProfName::new().unwrap();
+///
+///     if prof_name.set("worker 1").is_ok() {
+///         assert_eq!(prof_name.get().unwrap(), "worker 1");
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfName(Mib<*const c_char, [usize; 3]>);
+
+impl ProfName {
+    /// Returns a new `ProfName`.
+    pub fn new() -> Result<ProfName> {
+        unsafe { Mib::new(PROF_NAME).map(ProfName) }
+    }
+
+    /// Returns the name associated with the current thread in memory profile dumps.
+    ///
+    /// The name is copied, since jemalloc frees it when the thread's name is changed.
+    pub fn get(&self) -> Result<String> {
+        let ptr = self.0.read()?;
+        let name = unsafe { CStr::from_ptr(ptr) };
+        name.to_str()
+            .map(|name| name.to_string())
+            .map_err(|error| Error::Utf8 {
+                name: name_string(PROF_NAME),
+                error,
+            })
+    }
+
+    /// Sets the name associated with the current thread in memory profile dumps.
+    pub fn set<S>(&self, name: &S) -> Result<()>
+    where
+        S: ToCStr + ?Sized,
+    {
+        unsafe { set_str_mib(self.0.as_slice(), name, &|| name_string(PROF_NAME)) }
+    }
+}
+
+const PROF_ACTIVE: &[u8] = b"thread.prof.active\0";
+
+/// Determines if sampling is active for the current thread.
+///
+/// Allocations are only sampled if both this and the global [`prof::active`] flag are set.
+///
+/// This corresponds to `thread.prof.active` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     if let Ok(active) = jemalloc_ctl::thread::prof_active() {
+///         println!("sampling active for this thread: {}", active);
+///     }
+/// }
+/// ```
+///
+/// [`prof::active`]: ../prof/fn.active.html
+pub fn prof_active() -> Result<bool> {
+    unsafe { get(PROF_ACTIVE) }
+}
+
+/// Activates or deactivates sampling for the current thread.
+///
+/// This corresponds to `thread.prof.active` in jemalloc's API.
+pub fn set_prof_active(prof_active: bool) -> Result<()> {
+    unsafe { set(PROF_ACTIVE, prof_active) }
+}
+
+/// A type providing access to the state of sampling for the current thread.
+///
+/// This corresponds to `thread.prof.active` in jemalloc's API.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::thread::ProfActive;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let prof_active = ProfActive::new().unwrap();
+///
+///     if prof_active.set(false).is_ok() {
+///         assert!(!prof_active.get().unwrap());
+///     }
+/// }
+/// ```
+#[derive(Copy, Clone)]
+pub struct ProfActive(Mib<bool, [usize; 3]>);
+
+impl ProfActive {
+    /// Returns a new `ProfActive`.
+    pub fn new() -> Result<ProfActive> {
+        unsafe { Mib::new(PROF_ACTIVE).map(ProfActive) }
+    }
+
+    /// Determines if sampling is active for the current thread.
+    pub fn get(&self) -> Result<bool> {
+        self.0.read()
+    }
+
+    /// Activates or deactivates sampling for the current thread.
+    pub fn set(&self, prof_active: bool) -> Result<()> {
+        self.0.write(prof_active)
+    }
+}
+
+/// A meter of the memory allocated and deallocated by the current thread.
+///
+/// The meter snapshots the thread's [`allocatedp`] and [`deallocatedp`] counters when it is
+/// created, and reports the number of bytes allocated and deallocated since then. Reading the
+/// meter is cheap, so it can be used to attribute allocation cost to individual units of work,
+/// such as requests handled by the thread.
+///
+/// Like [`ThreadLocal`], it is neither `Sync` nor `Send`.
+///
+/// # Examples
+///
+/// ```
+/// extern crate jemallocator;
+/// extern crate jemalloc_ctl;
+///
+/// use jemalloc_ctl::thread::AllocationMeter;
+///
+/// #[global_allocator]
+/// static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+///
+/// fn main() {
+///     let meter = AllocationMeter::new().unwrap();
+///
+///     let buf = vec![0u8; 1024 * 1024];
+///     assert!(meter.allocated() >= 1024 * 1024);
+///     assert!(meter.net() >= 1024 * 1024);
+///
+///     drop(buf);
+///     assert!(meter.deallocated() >= 1024 * 1024);
+///     assert_eq!(meter.net(), meter.allocated() as i64 - meter.deallocated() as i64);
+/// }
+/// ```
+///
+/// [`allocatedp`]: fn.allocatedp.html
+/// [`deallocatedp`]: fn.deallocatedp.html
+/// [`ThreadLocal`]: struct.ThreadLocal.html
+#[derive(Copy, Clone)]
+pub struct AllocationMeter {
+    allocated: ThreadLocal<u64>,
+    deallocated: ThreadLocal<u64>,
+    start_allocated: u64,
+    start_deallocated: u64,
+}
+
+impl AllocationMeter {
+    /// Returns a new `AllocationMeter` for the current thread.
+    pub fn new() -> Result<AllocationMeter> {
+        let allocated = allocatedp()?;
+        let deallocated = deallocatedp()?;
+        Ok(AllocationMeter {
+            allocated,
+            deallocated,
+            start_allocated: allocated.get(),
+            start_deallocated: deallocated.get(),
+        })
+    }
+
+    /// Returns the number of bytes allocated by the current thread since the meter was created or
+    /// last reset.
+    #[inline]
+    pub fn allocated(&self) -> u64 {
+        self.allocated.get().wrapping_sub(self.start_allocated)
+    }
+
+    /// Returns the number of bytes deallocated by the current thread since the meter was created
+    /// or last reset.
+    #[inline]
+    pub fn deallocated(&self) -> u64 {
+        self.deallocated.get().wrapping_sub(self.start_deallocated)
+    }
+
+    /// Returns the net number of bytes allocated by the current thread since the meter was
+    /// created or last reset.
+    ///
+    /// This is negative if the thread deallocated more memory than it allocated, such as memory
+    /// allocated before the meter was created or by another thread.
+    #[inline]
+    pub fn net(&self) -> i64 {
+        self.allocated().wrapping_sub(self.deallocated()) as i64
+    }
+
+    /// Restarts the meter from the current values of the thread's counters.
+    pub fn reset(&mut self) {
+        self.start_allocated = self.allocated.get();
+        self.start_deallocated = self.deallocated.get();
     }
 }
 
@@ -210,3 +971,55 @@ where
         unsafe { *self.0 }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use arenas;
+
+    #[test]
+    fn guards_nest() {
+        let original = arena().unwrap();
+        let a = arenas::create().unwrap();
+        let b = arenas::create().unwrap();
+
+        {
+            let guard_a = bind_arena(a).unwrap();
+            assert_eq!(guard_a.previous(), original);
+            {
+                let guard_b = bind_arena(b).unwrap();
+                assert_eq!(guard_b.previous(), a);
+                assert_eq!(arena().unwrap(), b);
+                guard_b.restore().unwrap();
+            }
+            assert_eq!(arena().unwrap(), a);
+        }
+        assert_eq!(arena().unwrap(), original);
+    }
+
+    #[test]
+    fn bind_invalid_arena() {
+        assert!(bind_arena(ArenaId::from(arenas::ALL)).is_err());
+        assert!(arena().unwrap() != ArenaId::from(arenas::ALL));
+    }
+
+    #[test]
+    fn meter_counts_thread() {
+        let mut meter = AllocationMeter::new().unwrap();
+        let buf = vec![0u8; 64 * 1024];
+        assert!(meter.allocated() >= 64 * 1024);
+
+        // allocations on other threads are not counted
+        let before = meter.allocated();
+        ::std::thread::spawn(|| drop(vec![0u8; 1024 * 1024]))
+            .join()
+            .unwrap();
+        assert!(meter.allocated() - before < 1024 * 1024);
+
+        meter.reset();
+        drop(buf);
+        assert!(meter.deallocated() >= 64 * 1024);
+        assert!(meter.net() <= -64 * 1024);
+        assert_eq!(allocated().unwrap(), allocatedp().unwrap().get());
+    }
+}